@@ -0,0 +1,20 @@
+use std::io::Write;
+
+use crate::input::InputArgs;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+
+    let mut count = args.input.csv(&buf).count_records();
+    if !args.input.no_headers {
+        count = count.saturating_sub(1);
+    }
+    writeln!(out, "{count}")?;
+    Ok(())
+}
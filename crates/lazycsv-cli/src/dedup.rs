@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use anyhow::Context;
+use lazycsv::{dedup::Dedup, io::CsvReader};
+
+use crate::{input::InputArgs, records::write_raw};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    // Stream the input, so that only a hash of each distinct record is kept in memory.
+    let mut records = CsvReader::with_separator(args.input.reader()?, args.input.delimiter);
+
+    if !args.input.no_headers
+        && let Some(header) = records.next()
+    {
+        let header = header.with_context(|| args.input.read_error())?;
+        write_raw(out, header.as_bytes())?;
+    }
+
+    for record in Dedup::new(records) {
+        let record = record.with_context(|| args.input.read_error())?;
+        write_raw(out, record.as_bytes())?;
+    }
+    Ok(())
+}
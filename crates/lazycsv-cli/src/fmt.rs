@@ -0,0 +1,55 @@
+use std::io::Write;
+
+use lazycsv::LineEnding;
+
+use crate::{
+    input::{InputArgs, parse_separator},
+    records::{LineEndingArg, Records},
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Separator character of the output, e.g. `;` or `\t`.
+    #[arg(short, long, value_parser = parse_separator)]
+    out_delimiter: u8,
+
+    /// Line break of the output.
+    #[arg(long, value_enum, default_value_t)]
+    line_ending: LineEndingArg,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+    let line_ending = LineEnding::from(args.line_ending).as_bytes();
+    for record in Records::new(args.input.csv(&buf), &buf) {
+        for (i, cell) in record.cells.iter().enumerate() {
+            if i != 0 {
+                out.write_all(&[args.out_delimiter])?;
+            }
+            write_cell(out, cell.buf, args.out_delimiter)?;
+        }
+        out.write_all(line_ending)?;
+    }
+    Ok(())
+}
+
+/// Writes a raw cell, quoting it if it contains the new separator.
+///
+/// Quoted cells are written as-is, since their escaping doesn't depend on the separator.
+fn write_cell(out: &mut impl Write, cell: &[u8], separator: u8) -> std::io::Result<()> {
+    if cell.starts_with(b"\"") || memchr::memchr3(separator, b'\r', b'"', cell).is_none() {
+        return out.write_all(cell);
+    }
+
+    out.write_all(b"\"")?;
+    for (i, part) in cell.split(|&c| c == b'"').enumerate() {
+        if i != 0 {
+            out.write_all(b"\"\"")?;
+        }
+        out.write_all(part)?;
+    }
+    out.write_all(b"\"")
+}
@@ -0,0 +1,20 @@
+use std::io::Write;
+
+use crate::{input::InputArgs, records::Records};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+    let Some(header) = Records::new(args.input.csv(&buf), &buf).next() else {
+        return Ok(());
+    };
+    for (i, cell) in header.cells.iter().enumerate() {
+        writeln!(out, "{i}\t{}", cell.try_as_str()?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, bail};
+use lazycsv::{
+    Csv,
+    encoding::{Encoding, UTF_8, to_utf8},
+    io::AutoDecoder,
+};
+
+/// Arguments shared by every subcommand that reads CSV.
+#[derive(clap::Args, Debug)]
+pub struct InputArgs {
+    /// Input file, which may be compressed with gzip or zstd. Reads from the standard input if
+    /// omitted or `-`.
+    pub input: Option<PathBuf>,
+
+    /// Separator character of the input, e.g. `,`, `;` or `\t`.
+    #[arg(short, long, default_value = ",", value_parser = parse_separator)]
+    pub delimiter: u8,
+
+    /// Treat the first record as data instead of a header row.
+    #[arg(long)]
+    pub no_headers: bool,
+
+    /// Transcode the input from the given encoding, e.g. `cp949`, `windows-1252` or `utf-16le`.
+    /// A byte order mark in the input takes precedence, and is stripped even if this is omitted.
+    #[arg(long, value_parser = parse_encoding)]
+    pub encoding: Option<&'static Encoding>,
+}
+
+impl InputArgs {
+    /// Reads the whole input into memory, decompressing and transcoding it if necessary.
+    pub fn read(&self) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.open()?
+            .read_to_end(&mut buf)
+            .with_context(|| self.read_error())?;
+        self.transcode(buf)
+    }
+
+    /// Opens the input as a stream, decompressing it if necessary.
+    ///
+    /// A UTF-8 BOM is stripped on the fly. Input in another encoding has to be transcoded as a
+    /// whole, so it's read into memory as [`InputArgs::read()`] does.
+    pub fn reader(&self) -> anyhow::Result<Box<dyn Read>> {
+        let mut reader = self.open()?;
+        let mut head = Vec::with_capacity(3);
+        (&mut reader)
+            .take(3)
+            .read_to_end(&mut head)
+            .with_context(|| self.read_error())?;
+        match (self.encoding, Encoding::for_bom(&head)) {
+            (None, None) => Ok(Box::new(Cursor::new(head).chain(reader))),
+            (None, Some((encoding, bom_len))) if encoding == UTF_8 => {
+                head.drain(..bom_len);
+                Ok(Box::new(Cursor::new(head).chain(reader)))
+            }
+            _ => {
+                let mut buf = head;
+                reader
+                    .read_to_end(&mut buf)
+                    .with_context(|| self.read_error())?;
+                Ok(Box::new(Cursor::new(self.transcode(buf)?)))
+            }
+        }
+    }
+
+    /// Creates a parser for the given buffer with the configured separator.
+    pub fn csv<'a>(&self, buf: &'a [u8]) -> Csv<'a> {
+        Csv::with_separator(buf, self.delimiter)
+    }
+
+    /// Returns the context of errors while reading the input.
+    pub fn read_error(&self) -> String {
+        match &self.input {
+            Some(path) if path.as_os_str() != "-" => format!("failed to read {}", path.display()),
+            _ => "failed to read the standard input".to_owned(),
+        }
+    }
+
+    fn open(&self) -> anyhow::Result<AutoDecoder<Box<dyn BufRead>>> {
+        let reader: Box<dyn BufRead> = match &self.input {
+            Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(
+                File::open(path).with_context(|| self.read_error())?,
+            )),
+            _ => Box::new(io::stdin().lock()),
+        };
+        AutoDecoder::new(reader).with_context(|| self.read_error())
+    }
+
+    fn transcode(&self, buf: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        // Without a BOM or `--encoding`, the input is passed through as-is.
+        match self
+            .encoding
+            .or_else(|| Encoding::for_bom(&buf).map(|(encoding, _)| encoding))
+        {
+            Some(encoding) => Ok(to_utf8(&buf, encoding)?.into_owned().into_bytes()),
+            None => Ok(buf),
+        }
+    }
+}
+
+/// Parses an encoding label, accepting `cp949` as an alias of EUC-KR.
+pub fn parse_encoding(s: &str) -> anyhow::Result<&'static Encoding> {
+    match s.to_ascii_lowercase().as_str() {
+        "cp949" => Ok(lazycsv::encoding::EUC_KR),
+        label => {
+            Encoding::for_label(label.as_bytes()).with_context(|| format!("unknown encoding {s:?}"))
+        }
+    }
+}
+
+/// Parses a single-byte separator, accepting `\t` and `tab` for convenience.
+pub fn parse_separator(s: &str) -> anyhow::Result<u8> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() && !matches!(s, "\"" | "\r" | "\n") => {
+            Ok(s.as_bytes()[0])
+        }
+        _ => bail!("separator must be a single ASCII character other than quotes and line breaks"),
+    }
+}
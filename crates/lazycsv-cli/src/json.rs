@@ -0,0 +1,42 @@
+use std::io::Write;
+
+use anyhow::bail;
+use lazycsv::json::{JsonFormat, JsonOptions, write_json};
+
+use crate::{input::InputArgs, records::LineEndingArg};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Write a single JSON array instead of JSON Lines.
+    #[arg(long)]
+    array: bool,
+
+    /// Write numbers and booleans unquoted in columns that only contain them.
+    #[arg(long)]
+    infer_types: bool,
+
+    /// Line break of the output.
+    #[arg(long, value_enum, default_value_t)]
+    line_ending: LineEndingArg,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    if args.input.no_headers {
+        bail!("converting to JSON requires a header row");
+    }
+    let buf = args.input.read()?;
+    let options = JsonOptions {
+        format: if args.array {
+            JsonFormat::Array
+        } else {
+            JsonFormat::Lines
+        },
+        infer_types: args.infer_types,
+        line_ending: args.line_ending.into(),
+    };
+    write_json(args.input.csv(&buf), out, options)?;
+    Ok(())
+}
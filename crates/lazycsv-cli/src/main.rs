@@ -0,0 +1,83 @@
+//! `lazycsv` command-line tool.
+//!
+//! See the [README](https://github.com/contentstech-com/crates/tree/main/crates/lazycsv-cli) for
+//! usage examples.
+
+mod count;
+mod dedup;
+mod fmt;
+mod headers;
+mod input;
+mod json;
+mod records;
+mod select;
+mod slice;
+mod validate;
+
+use std::{
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+
+/// Vectorized, lazy-decoding, zero-copy CSV toolkit.
+#[derive(Parser, Debug)]
+#[command(name = "lazycsv", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Count the records, excluding the header row.
+    Count(count::Args),
+    /// Print the header names along with their indices.
+    Headers(headers::Args),
+    /// Select columns by name or index.
+    Select(select::Args),
+    /// Print a range of records.
+    Slice(slice::Args),
+    /// Check column counts and quoting, reporting the line of each problem.
+    Validate(validate::Args),
+    /// Re-emit the records with a different separator.
+    Fmt(fmt::Args),
+    /// Convert to JSON Lines or JSON, using the header row as keys.
+    Json(json::Args),
+    /// Remove duplicated records, keeping the first occurrence. Records that only differ in
+    /// quoting are duplicates.
+    Dedup(dedup::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = BufWriter::new(io::stdout().lock());
+    let result = match cli.command {
+        Command::Count(args) => count::run(args, &mut out),
+        Command::Headers(args) => headers::run(args, &mut out),
+        Command::Select(args) => select::run(args, &mut out),
+        Command::Slice(args) => slice::run(args, &mut out),
+        Command::Validate(args) => validate::run(args, &mut out),
+        Command::Fmt(args) => fmt::run(args, &mut out),
+        Command::Json(args) => json::run(args, &mut out),
+        Command::Dedup(args) => dedup::run(args, &mut out),
+    }
+    .and_then(|()| Ok(out.flush()?));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // Stop silently when piped into a command like `head`.
+        Err(err)
+            if err
+                .downcast_ref::<io::Error>()
+                .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("lazycsv: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
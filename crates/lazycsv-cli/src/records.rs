@@ -0,0 +1,113 @@
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+use lazycsv::{Cell, Csv, CsvIterItem, LineEnding};
+
+/// A record with an arbitrary number of cells.
+#[derive(Clone, Debug)]
+pub struct Record<'a> {
+    pub cells: Vec<Cell<'a>>,
+    /// Byte range of the record, including its line break.
+    pub range: Range<usize>,
+    /// 1-based line number where the record starts.
+    pub line: usize,
+}
+
+/// An iterator over records whose column count is only known at runtime.
+#[derive(Clone, Debug)]
+pub struct Records<'a> {
+    csv: Csv<'a>,
+    buf: &'a [u8],
+    line: usize,
+    /// Position of an unterminated quoted cell, if the parser stopped at one.
+    pub unterminated_quote: Option<usize>,
+}
+
+impl<'a> Records<'a> {
+    pub fn new(csv: Csv<'a>, buf: &'a [u8]) -> Self {
+        Self {
+            csv,
+            buf,
+            line: 1,
+            unterminated_quote: None,
+        }
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.csv.position();
+        let mut cells = Vec::new();
+        loop {
+            let position = self.csv.position();
+            match self.csv.next() {
+                Some(CsvIterItem::Cell(cell)) => cells.push(cell),
+                Some(CsvIterItem::LineEnd) => break,
+                None => {
+                    // The parser only gives up on remaining data when a quote is left open.
+                    if position < self.buf.len() {
+                        self.unterminated_quote = Some(position);
+                    }
+                    if cells.is_empty() {
+                        return None;
+                    }
+                    break;
+                }
+            }
+        }
+
+        let end = self.csv.position();
+        let line = self.line;
+        self.line += memchr::memchr_iter(b'\n', &self.buf[start..end]).count();
+        Some(Record {
+            cells,
+            range: start..end,
+            line,
+        })
+    }
+}
+
+/// Writes raw cells separated by `separator`, followed by a line break.
+pub fn write_record<'a>(
+    out: &mut impl Write,
+    cells: impl IntoIterator<Item = &'a [u8]>,
+    separator: u8,
+) -> io::Result<()> {
+    for (i, cell) in cells.into_iter().enumerate() {
+        if i != 0 {
+            out.write_all(&[separator])?;
+        }
+        out.write_all(cell)?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Writes a record verbatim, so quoting and line endings are preserved.
+pub fn write_raw(out: &mut impl Write, record: &[u8]) -> io::Result<()> {
+    out.write_all(record)?;
+    if !record.ends_with(b"\n") {
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Line break of the output.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum LineEndingArg {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl From<LineEndingArg> for LineEnding {
+    fn from(arg: LineEndingArg) -> Self {
+        match arg {
+            LineEndingArg::Lf => LineEnding::Lf,
+            LineEndingArg::Crlf => LineEnding::Crlf,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::Write;
+
+use anyhow::{Context, bail};
+
+use crate::{
+    input::InputArgs,
+    records::{Records, write_record},
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Comma-separated list of column names or 0-based indices, e.g. `ISRC,3`.
+    columns: String,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+    let mut records = Records::new(args.input.csv(&buf), &buf).peekable();
+
+    let header = if args.input.no_headers {
+        None
+    } else {
+        records.peek()
+    };
+    let indices = args
+        .columns
+        .split(',')
+        .map(|column| {
+            if let Ok(index) = column.parse::<usize>() {
+                return Ok(index);
+            }
+            let Some(header) = header else {
+                bail!("column {column:?} must be an index when --no-headers is set");
+            };
+            for (i, cell) in header.cells.iter().enumerate() {
+                if cell.try_as_str()? == column {
+                    return Ok(i);
+                }
+            }
+            bail!("no column named {column:?}");
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    for record in records {
+        let cells =
+            indices
+                .iter()
+                .map(|&i| {
+                    record.cells.get(i).map(|cell| cell.buf).with_context(|| {
+                        format!("record at line {} has no column {i}", record.line)
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+        write_record(out, cells, args.input.delimiter)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::io::Write;
+
+use crate::{
+    input::InputArgs,
+    records::{Records, write_raw},
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Index of the first record to print, not counting the header row.
+    #[arg(short, long, default_value_t = 0)]
+    start: usize,
+
+    /// Index of the record to stop before.
+    #[arg(short, long, conflicts_with = "len")]
+    end: Option<usize>,
+
+    /// Number of records to print.
+    #[arg(short, long)]
+    len: Option<usize>,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+    let mut records = Records::new(args.input.csv(&buf), &buf);
+
+    if !args.input.no_headers
+        && let Some(header) = records.next()
+    {
+        write_raw(out, &buf[header.range])?;
+    }
+
+    let len = match (args.end, args.len) {
+        (Some(end), _) => end.saturating_sub(args.start),
+        (None, Some(len)) => len,
+        (None, None) => usize::MAX,
+    };
+    for record in records.skip(args.start).take(len) {
+        write_raw(out, &buf[record.range])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::io::Write;
+
+use anyhow::bail;
+
+use crate::{input::InputArgs, records::Records};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Expected number of columns. Defaults to the column count of the first record.
+    #[arg(short, long)]
+    columns: Option<usize>,
+
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: Args, out: &mut impl Write) -> anyhow::Result<()> {
+    let buf = args.input.read()?;
+    let mut records = Records::new(args.input.csv(&buf), &buf);
+
+    let mut expected = args.columns;
+    let mut problems = 0;
+    for record in records.by_ref() {
+        let expected = *expected.get_or_insert(record.cells.len());
+        if record.cells.len() != expected {
+            problems += 1;
+            writeln!(
+                out,
+                "line {}: expected {expected} columns, found {}",
+                record.line,
+                record.cells.len(),
+            )?;
+        }
+        for (i, cell) in record.cells.iter().enumerate() {
+            if let Err(problem) = cell.validate_quoting() {
+                problems += 1;
+                writeln!(out, "line {}: column {i}: {problem}", record.line)?;
+            }
+        }
+    }
+
+    if let Some(position) = records.unterminated_quote {
+        problems += 1;
+        let line = 1 + memchr::memchr_iter(b'\n', &buf[..position]).count();
+        writeln!(out, "line {line}: quoted cell is never closed")?;
+    }
+
+    if problems != 0 {
+        bail!("found {problems} problem(s)");
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, Output, Stdio},
+};
+
+fn lazycsv(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lazycsv"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The command may exit without reading the whole input, e.g. on invalid arguments.
+    if let Err(err) = child.stdin.take().unwrap().write_all(stdin) {
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(args: &[&str], stdin: &[u8]) -> String {
+    let output = lazycsv(args, stdin);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const SALES: &[u8] = b"isrc,title,units\r\nUSRC17607839,\"Hello,\nworld\",3\r\nGBAYE0601498,\"Hi \"\"Quote\"\"\",5\r\n";
+
+#[test]
+fn count() {
+    assert_eq!(stdout(&["count"], SALES), "2\n");
+    assert_eq!(stdout(&["count", "--no-headers"], SALES), "3\n");
+    assert_eq!(stdout(&["count"], b"a\n1\n2"), "2\n");
+    assert_eq!(stdout(&["count"], b""), "0\n");
+    assert_eq!(stdout(&["count"], b"a\n\"1\n2\"\r\n3\n"), "2\n");
+}
+
+#[test]
+fn headers() {
+    assert_eq!(stdout(&["headers"], SALES), "0\tisrc\n1\ttitle\n2\tunits\n");
+}
+
+#[test]
+fn select() {
+    assert_eq!(
+        stdout(&["select", "units,0"], SALES),
+        "units,isrc\n3,USRC17607839\n5,GBAYE0601498\n",
+    );
+    assert_eq!(
+        stdout(&["select", "1", "--no-headers", "-d", ";"], b"a;\"b;c\"\n"),
+        "\"b;c\"\n",
+    );
+    assert!(!lazycsv(&["select", "missing"], SALES).status.success());
+}
+
+#[test]
+fn slice() {
+    assert_eq!(
+        stdout(&["slice", "--start", "1"], SALES),
+        "isrc,title,units\r\nGBAYE0601498,\"Hi \"\"Quote\"\"\",5\r\n",
+    );
+    assert_eq!(
+        stdout(&["slice", "--len", "1", "--no-headers"], b"a\nb\nc"),
+        "a\n"
+    );
+    assert_eq!(
+        stdout(&["slice", "-s", "1", "-e", "2"], b"h\na\nb\nc"),
+        "h\nb\n"
+    );
+}
+
+#[test]
+fn validate() {
+    assert_eq!(stdout(&["validate"], SALES), "");
+
+    let output = lazycsv(
+        &["validate"],
+        b"a,b\n1,2,3\n\"x\"y,2\nz\"\"z,\"a\"b\"\"\n\"open,2\n",
+    );
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "line 2: expected 2 columns, found 3\n\
+         line 3: column 0: unexpected data after the closing quote\n\
+         line 4: column 0: quote inside an unquoted cell\n\
+         line 4: column 1: unescaped quote inside a quoted cell\n\
+         line 5: quoted cell is never closed\n",
+    );
+}
+
+#[test]
+fn fmt() {
+    assert_eq!(
+        stdout(&["fmt", "--out-delimiter", "\\t"], SALES),
+        "isrc\ttitle\tunits\nUSRC17607839\t\"Hello,\nworld\"\t3\nGBAYE0601498\t\"Hi \"\"Quote\"\"\"\t5\n",
+    );
+    assert_eq!(
+        stdout(&["fmt", "-o", ";", "-d", "\\t"], b"a;b\tc\n"),
+        "\"a;b\";c\n"
+    );
+    assert_eq!(
+        stdout(
+            &["fmt", "-o", ",", "--line-ending", "crlf"],
+            b"a,\"b\nc\"\n1,2\r\n"
+        ),
+        "a,\"b\nc\"\r\n1,2\r\n"
+    );
+}
+
+#[test]
+fn json() {
+    assert_eq!(
+        stdout(&["json", "--infer-types"], SALES),
+        "{\"isrc\":\"USRC17607839\",\"title\":\"Hello,\\nworld\",\"units\":3}\n\
+         {\"isrc\":\"GBAYE0601498\",\"title\":\"Hi \\\"Quote\\\"\",\"units\":5}\n",
+    );
+    assert_eq!(
+        stdout(&["json", "--array"], b"a\n1\n"),
+        "[\n{\"a\":\"1\"}\n]\n"
+    );
+    assert_eq!(
+        stdout(&["json", "--line-ending", "crlf"], b"a\n1\n2\n"),
+        "{\"a\":\"1\"}\r\n{\"a\":\"2\"}\r\n"
+    );
+    assert!(!lazycsv(&["json", "--no-headers"], SALES).status.success());
+}
+
+#[test]
+fn dedup() {
+    assert_eq!(
+        stdout(
+            &["dedup"],
+            b"a,b\nx,1\n\"x\",\"1\"\r\ny,1\nx,1,\na,b\nx,\"1\""
+        ),
+        "a,b\nx,1\ny,1\nx,1,\na,b\n",
+    );
+    assert_eq!(stdout(&["dedup", "--no-headers"], b"a\na\n"), "a\n");
+    let output = lazycsv(&["dedup", "--no-headers"], b"a\xff,b\na,\xffb\n");
+    assert_eq!(output.stdout, b"a\xff,b\na,\xffb\n");
+    assert_eq!(
+        stdout(&["dedup", "-d", ";"], b"h\na;b\na,b\n\"a\";b"),
+        "h\na;b\na,b\n"
+    );
+
+    // The input is streamed, stripping a BOM on the fly unless it has to be transcoded.
+    assert_eq!(stdout(&["dedup"], b"\xef\xbb\xbfa\na\nb\na\n"), "a\na\nb\n");
+    assert_eq!(
+        stdout(&["dedup", "--encoding", "cp949"], b"\xc0\xcc\n1\n1\n"),
+        "이\n1\n"
+    );
+    assert!(!lazycsv(&["dedup"], b"a\n\"b\n").status.success());
+}
+
+#[cfg(all(feature = "gzip", feature = "zstd"))]
+#[test]
+fn compressed_input() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(SALES).unwrap();
+    let gzip = encoder.finish().unwrap();
+    assert_eq!(stdout(&["count"], &gzip), "2\n");
+
+    let zstd = zstd::encode_all(SALES, 0).unwrap();
+    assert_eq!(
+        stdout(&["select", "isrc"], &zstd),
+        "isrc\nUSRC17607839\nGBAYE0601498\n"
+    );
+}
+
+#[test]
+fn encoding() {
+    // "이름,값" in CP949
+    let cp949 = b"\xc0\xcc\xb8\xa7,\xb0\xaa\n1,2\n";
+    assert_eq!(
+        stdout(&["json", "--encoding", "cp949"], cp949),
+        "{\"이름\":\"1\",\"값\":\"2\"}\n"
+    );
+    assert!(
+        !lazycsv(&["json", "--encoding", "utf-8"], cp949)
+            .status
+            .success()
+    );
+    assert!(
+        !lazycsv(&["count", "--encoding", "nope"], cp949)
+            .status
+            .success()
+    );
+
+    // The BOM takes precedence over the declared encoding.
+    let utf16: Vec<u8> = [0xfeff]
+        .into_iter()
+        .chain("a,b\n1,2\n".encode_utf16())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    assert_eq!(
+        stdout(&["select", "--encoding", "windows-1252", "b"], &utf16),
+        "b\n2\n"
+    );
+
+    // A BOM is detected without `--encoding` too, so it doesn't end up in the first header.
+    assert_eq!(
+        stdout(&["select", "a"], b"\xef\xbb\xbfa,b\n1,2\n"),
+        "a\n1\n"
+    );
+    assert_eq!(stdout(&["select", "b"], &utf16), "b\n2\n");
+}
@@ -0,0 +1,193 @@
+//! Derive macro for [`lazycsv::typed::FromRow`](https://docs.rs/lazycsv/latest/lazycsv/typed/trait.FromRow.html).
+//!
+//! This crate is not meant to be used directly. Enable the `derive` feature of `lazycsv` instead,
+//! which re-exports the macro as `lazycsv::typed::FromRow`.
+
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{
+    Data, DeriveInput, ExprPath, Fields, GenericParam, Lifetime, LifetimeParam, LitStr, Type,
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned,
+};
+
+/// Derives `lazycsv::typed::FromRow` for a struct.
+///
+/// See the documentation of the re-export in `lazycsv` for details.
+#[proc_macro_derive(FromRow, attributes(csv))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Options of a field, given with `#[csv(...)]`.
+struct FieldAttrs {
+    name: Option<LitStr>,
+    with: Option<ExprPath>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = FieldAttrs {
+            name: None,
+            with: None,
+        };
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("csv")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    parsed.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("with") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    parsed.with = Some(path.parse()?);
+                } else {
+                    return Err(meta.error("expected `name` or `with`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span,
+                "FromRow can only be derived for structs",
+            ));
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "FromRow can only be derived for structs",
+            ));
+        }
+    };
+    if fields.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "FromRow requires at least one field",
+        ));
+    }
+
+    // Borrow cells for the first lifetime of the struct, or a new one if it has none.
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__lazycsv", Span::call_site());
+            let param = GenericParam::Lifetime(LifetimeParam::new(lifetime.clone()));
+            generics.params.insert(0, param);
+            lifetime
+        }
+    };
+    let has_type_params = input.generics.type_params().next().is_some();
+
+    let mut values = Vec::new();
+    let mut headers = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let ty = &field.ty;
+        let optional = is_option(ty);
+
+        let value = match &attrs.with {
+            Some(with) => quote_spanned! {with.span()=>
+                cells.next_cell_with(#with)?
+            },
+            None => {
+                if has_type_params {
+                    generics
+                        .make_where_clause()
+                        .predicates
+                        .push(parse_quote!(#ty: ::lazycsv::typed::FromCell<#lifetime>));
+                }
+                quote_spanned! {ty.span()=>
+                    cells.next_cell::<#ty>()?
+                }
+            }
+        };
+        values.push(match optional {
+            true => quote! {
+                if cells.skip_missing() {
+                    ::core::option::Option::None
+                } else {
+                    #value
+                }
+            },
+            false => value,
+        });
+
+        match &field.ident {
+            Some(ident) => {
+                let name = match attrs.name {
+                    Some(name) => name,
+                    None => LitStr::new(&ident.unraw().to_string(), ident.span()),
+                };
+                headers.push(quote! {
+                    ::lazycsv::typed::HeaderField { name: #name, optional: #optional }
+                });
+            }
+            None => {
+                if let Some(name) = attrs.name {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("field {index} of a tuple struct can't be mapped by header name"),
+                    ));
+                }
+            }
+        }
+    }
+
+    let cols = fields.len();
+    let (construct, headers) = match fields {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|field| &field.ident);
+            (
+                quote! { Self { #(#idents: #values,)* } },
+                quote! { ::core::option::Option::Some(&[#(#headers),*]) },
+            )
+        }
+        _ => (
+            quote! { Self(#(#values,)*) },
+            quote! { ::core::option::Option::None },
+        ),
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lazycsv::typed::FromRow<#lifetime> for #ident #ty_generics
+        #where_clause
+        {
+            const COLS: usize = #cols;
+            const HEADERS: ::core::option::Option<&'static [::lazycsv::typed::HeaderField]> =
+                #headers;
+
+            fn from_row(
+                cells: &mut ::lazycsv::typed::RowCells<'_, #lifetime>,
+            ) -> ::core::result::Result<Self, ::lazycsv::typed::TypedRowError> {
+                ::core::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+/// Whether the type is spelled as `Option<T>`, whose column may be missing from the header.
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+}
@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+
+use lazycsv::{
+    Cell, Csv, RowIterError,
+    typed::{FromCellError, FromRow, HeaderField, TypedRowError},
+};
+
+#[derive(FromRow, Debug, PartialEq)]
+struct Song<'a> {
+    #[csv(name = "ISRC")]
+    isrc: &'a str,
+    title: Cow<'a, str>,
+    #[csv(name = "Units")]
+    units: Option<u64>,
+}
+
+#[derive(FromRow, Debug, PartialEq)]
+struct Pair(u32, #[csv(with = "parse_hex")] u32);
+
+#[derive(FromRow, Debug, PartialEq)]
+struct Wrapper<T> {
+    r#value: T,
+}
+
+fn parse_hex(cell: Cell<'_>) -> Result<u32, FromCellError> {
+    let s: &str = lazycsv::typed::FromCell::from_cell(cell)?;
+    u32::from_str_radix(s, 16).map_err(Into::into)
+}
+
+#[test]
+fn derive_constants() {
+    assert_eq!(<Song as FromRow>::COLS, 3);
+    assert_eq!(
+        <Song as FromRow>::HEADERS,
+        Some(
+            &[
+                HeaderField {
+                    name: "ISRC",
+                    optional: false,
+                },
+                HeaderField {
+                    name: "title",
+                    optional: false,
+                },
+                HeaderField {
+                    name: "Units",
+                    optional: true,
+                },
+            ][..]
+        )
+    );
+    assert_eq!(<Pair as FromRow>::COLS, 2);
+    assert_eq!(<Pair as FromRow>::HEADERS, None);
+    assert_eq!(<Wrapper<u8> as FromRow>::HEADERS.unwrap()[0].name, "value");
+}
+
+#[test]
+fn derive_positional() {
+    let rows: Vec<_> = Csv::new(b"1,ff\n2,x\n3\n")
+        .into_typed_rows::<Pair>()
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            Ok(Pair(1, 255)),
+            Err(TypedRowError::Cell {
+                column: 1,
+                position: 7,
+                source: u32::from_str_radix("x", 16).unwrap_err().into(),
+            }),
+            Err(TypedRowError::Row(
+                RowIterError::ColumnCountSmallerThanExpected {
+                    expected: 2,
+                    actual: 1,
+                }
+            )),
+        ]
+    );
+
+    let rows: Vec<_> = Csv::new(b"USRC17607839,\"Hi, \"\"Quote\"\"\",3\nGBAYE0601498,b,\n")
+        .into_typed_rows::<Song>()
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            Ok(Song {
+                isrc: "USRC17607839",
+                title: Cow::Owned("Hi, \"Quote\"".to_string()),
+                units: Some(3),
+            }),
+            Ok(Song {
+                isrc: "GBAYE0601498",
+                title: Cow::Borrowed("b"),
+                units: None,
+            }),
+        ]
+    );
+
+    let mut rows = Csv::new(b"7\n").into_typed_rows::<Wrapper<i8>>();
+    assert_eq!(rows.next(), Some(Ok(Wrapper { value: 7 })));
+}
+
+#[test]
+fn derive_by_header() {
+    let data =
+        b"Units,\"title\",Extra,ISRC\n3,Song A,x,USRC17607839\n,Song B,y,GBAYE0601498\n1,Song C\n";
+    let rows: Vec<_> = Csv::new(data)
+        .into_typed_rows_by_header::<Song>()
+        .unwrap()
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            Ok(Song {
+                isrc: "USRC17607839",
+                title: Cow::Borrowed("Song A"),
+                units: Some(3),
+            }),
+            Ok(Song {
+                isrc: "GBAYE0601498",
+                title: Cow::Borrowed("Song B"),
+                units: None,
+            }),
+            Err(TypedRowError::Row(
+                RowIterError::ColumnCountSmallerThanExpected {
+                    expected: 4,
+                    actual: 2,
+                }
+            )),
+        ]
+    );
+
+    // Optional columns may be missing from the header.
+    let rows: Vec<_> = Csv::new(b"title,ISRC\nSong A,USRC17607839\n")
+        .into_typed_rows_by_header::<Song>()
+        .unwrap()
+        .collect();
+    assert_eq!(
+        rows,
+        [Ok(Song {
+            isrc: "USRC17607839",
+            title: Cow::Borrowed("Song A"),
+            units: None,
+        })]
+    );
+
+    let result = Csv::new(b"title,Units\nSong A,3\n").into_typed_rows_by_header::<Song>();
+    assert_eq!(
+        result.err(),
+        Some(TypedRowError::MissingColumn { name: "ISRC" })
+    );
+}
+
+#[test]
+fn derive_from_cells() {
+    let data = b"1,ff,2\n";
+    let row = Csv::new(data)
+        .into_rows::<{ <Pair as FromRow>::COLS }>()
+        .next();
+    assert_eq!(
+        row.unwrap().map(|cells| Pair::from_cells(&cells, 0)),
+        Err(RowIterError::ColumnCountLargerThanExpected { expected: 2 })
+    );
+
+    let mut rows = Csv::new(b"1,ff\n2,zz\n").into_rows_with_range::<2>();
+    let (cells, range) = rows.next().unwrap().unwrap();
+    assert_eq!(Pair::from_cells(&cells, range.start), Ok(Pair(1, 255)));
+    let (cells, range) = rows.next().unwrap().unwrap();
+    assert_eq!(
+        Pair::from_cells(&cells, range.start),
+        Err(TypedRowError::Cell {
+            column: 1,
+            position: 7,
+            source: u32::from_str_radix("zz", 16).unwrap_err().into(),
+        })
+    );
+
+    // The trailing empty cell isn't a subslice of the buffer, but is still positioned correctly.
+    let error = Err(TypedRowError::Cell {
+        column: 1,
+        position: 2,
+        source: u32::from_str_radix("", 16).unwrap_err().into(),
+    });
+    let (cells, range) = Csv::new(b"1,")
+        .into_rows_with_range::<2>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(Pair::from_cells(&cells, range.start), error);
+    assert_eq!(
+        Csv::new(b"1,").into_typed_rows::<Pair>().next(),
+        Some(error)
+    );
+}
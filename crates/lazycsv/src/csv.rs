@@ -0,0 +1,150 @@
+//! Conversion into the record types of the [`csv`] crate.
+//!
+//! This makes it possible to use lazycsv for the hot paths of a codebase built around the `csv`
+//! crate, while passing the same [`ByteRecord`]s and [`StringRecord`]s to the rest of it. Cells
+//! are dequoted while being converted, as `csv` records hold unescaped fields.
+//!
+//! [`Csv::into_byte_records()`] and [`Csv::into_string_records()`] yield records with a
+//! [`Position`] attached, like [`csv::Reader::byte_records()`].
+//! Unlike a `csv` reader with the default settings, records may have different numbers of fields.
+//! Positions always point at the first byte of a record, whereas a `csv` reader points at the `\n`
+//! of the preceding CRLF line break.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::Csv;
+//!
+//! let data = b"isrc,title\nUSRC17607839,\"Song \"\"A\"\"\"\n";
+//! let mut records = Csv::new(data).into_string_records();
+//! let record = records.nth(1).unwrap()?;
+//! assert_eq!(record, vec!["USRC17607839", "Song \"A\""]);
+//! assert_eq!(record.position().map(|pos| (pos.byte(), pos.line())), Some((11, 2)));
+//!
+//! // Rows read with a fixed column count can be converted as well.
+//! for row in Csv::new(data).into_rows::<2>() {
+//!     let record = lazycsv::csv::to_byte_record(&row?);
+//!     assert_eq!(record.len(), 2);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::vec::Vec;
+
+use ::csv::{ByteRecord, FromUtf8Error, Position, StringRecord};
+use memchr::memchr_iter;
+
+use crate::{Cell, Csv, CsvIterItem};
+
+/// Converts a row of cells into a [`ByteRecord`], dequoting each cell.
+pub fn to_byte_record(cells: &[Cell<'_>]) -> ByteRecord {
+    let mut record = ByteRecord::with_capacity(0, cells.len());
+    let mut scratch = Vec::new();
+    for cell in cells {
+        push_cell(&mut record, *cell, &mut scratch);
+    }
+    record
+}
+
+/// Converts a row of cells into a [`StringRecord`], dequoting each cell.
+///
+/// # Errors
+///
+/// Returns an error if a cell is not valid UTF-8.
+pub fn to_string_record(cells: &[Cell<'_>]) -> Result<StringRecord, FromUtf8Error> {
+    StringRecord::from_byte_record(to_byte_record(cells))
+}
+
+/// Pushes the dequoted cell as a field of `record`.
+fn push_cell(record: &mut ByteRecord, cell: Cell<'_>, scratch: &mut Vec<u8>) {
+    let mut chunks = cell.unquoted_chunks();
+    match (chunks.next(), chunks.clone().next()) {
+        (None, _) => record.push_field(b""),
+        (Some(chunk), None) => record.push_field(chunk),
+        (Some(chunk), Some(_)) => {
+            scratch.clear();
+            scratch.extend_from_slice(chunk);
+            chunks.for_each(|chunk| scratch.extend_from_slice(chunk));
+            record.push_field(scratch);
+        }
+    }
+}
+
+/// An iterator that yields each row as a [`ByteRecord`] with its [`Position`].
+///
+/// Can be created by calling [`Csv::into_byte_records()`]. See the
+/// [module-level documentation](self) for more details.
+#[derive(Clone, Debug)]
+pub struct ByteRecordIter<'a> {
+    csv: Csv<'a>,
+    /// 1-based line number of the next record.
+    line: u64,
+    /// 0-based index of the next record.
+    record: u64,
+}
+
+impl<'a> ByteRecordIter<'a> {
+    pub(crate) fn new(csv: Csv<'a>) -> Self {
+        Self {
+            csv,
+            line: 1,
+            record: 0,
+        }
+    }
+}
+
+impl Iterator for ByteRecordIter<'_> {
+    type Item = ByteRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.csv.position();
+        let mut record = ByteRecord::new();
+        let mut scratch = Vec::new();
+        loop {
+            match self.csv.next() {
+                Some(CsvIterItem::Cell(cell)) => push_cell(&mut record, cell, &mut scratch),
+                Some(CsvIterItem::LineEnd) => break,
+                // If we reach EOF before reading any cells, there are no more rows available.
+                None if record.is_empty() => return None,
+                None => break,
+            }
+        }
+
+        let mut position = Position::new();
+        position
+            .set_byte(start as u64)
+            .set_line(self.line)
+            .set_record(self.record);
+        record.set_position(Some(position));
+
+        let end = self.csv.position();
+        self.line += memchr_iter(b'\n', &self.csv.buf[start..end]).count() as u64;
+        self.record += 1;
+        Some(record)
+    }
+}
+
+/// An iterator that yields each row as a [`StringRecord`] with its [`Position`].
+///
+/// Can be created by calling [`Csv::into_string_records()`]. See the
+/// [module-level documentation](self) for more details.
+#[derive(Clone, Debug)]
+pub struct StringRecordIter<'a> {
+    inner: ByteRecordIter<'a>,
+}
+
+impl<'a> StringRecordIter<'a> {
+    pub(crate) fn new(csv: Csv<'a>) -> Self {
+        Self {
+            inner: ByteRecordIter::new(csv),
+        }
+    }
+}
+
+impl Iterator for StringRecordIter<'_> {
+    type Item = Result<StringRecord, FromUtf8Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(StringRecord::from_byte_record)
+    }
+}
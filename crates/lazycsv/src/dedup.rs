@@ -0,0 +1,195 @@
+//! Detecting duplicated rows by hashing.
+//!
+//! [`hash_row()`] hashes the dequoted content of a row, so rows that only differ in quoting, e.g.
+//! `"abc",1` and `abc,"1"`, hash equal. [`Dedup`] builds on it to skip rows that were already
+//! seen. It only keeps a 64-bit hash of each distinct row rather than the row itself, so it uses
+//! memory for one hash per distinct row, regardless of how wide the rows are. Combined with
+//! [`io::CsvReader`](crate::io::CsvReader), input larger than memory can be deduplicated as long
+//! as the hashes fit. The flip side is that two distinct rows whose hashes collide are treated as
+//! duplicates, which is very unlikely but possible.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "std")]
+//! # {
+//! use lazycsv::{Csv, dedup::Dedup};
+//!
+//! let data = b"abc,1\n\"abc\",\"1\"\nabc,2\n";
+//! let rows: Vec<_> = Dedup::new(Csv::new(data).into_rows::<2>()).collect::<Result<_, _>>()?;
+//! assert_eq!(rows.len(), 2);
+//! assert_eq!(rows[1][1].buf, b"2");
+//! # }
+//! # Ok::<(), lazycsv::RowIterError>(())
+//! ```
+
+use core::{hash::Hasher, ops::Range};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, RandomState},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::Cell;
+
+/// Feeds the dequoted cells of a row into `state`.
+///
+/// Rows hash equal if their cells have equal dequoted content, regardless of how they are quoted.
+/// A trailing empty cell still counts, so `a` and `a,` hash differently.
+///
+/// # Example
+///
+/// ```
+/// use std::hash::{DefaultHasher, Hasher};
+///
+/// use lazycsv::{Cell, dedup::hash_row};
+///
+/// let hash = |cells: &[Cell]| {
+///     let mut hasher = DefaultHasher::new();
+///     hash_row(cells, &mut hasher);
+///     hasher.finish()
+/// };
+/// let quoted = [Cell { buf: b"\"abc\"" }, Cell { buf: b"\"1\"" }];
+/// let unquoted = [Cell { buf: b"abc" }, Cell { buf: b"1" }];
+/// assert_eq!(hash(&quoted), hash(&unquoted));
+/// assert_ne!(hash(&quoted[..1]), hash(&quoted));
+/// ```
+pub fn hash_row<H: Hasher>(cells: &[Cell<'_>], state: &mut H) {
+    // Each cell is prefixed with its length, so rows like `a\xff,b` and `a,\xffb` don't feed the
+    // same bytes into the hasher.
+    for cell in cells {
+        cell.hash_unquoted(state);
+    }
+}
+
+/// A row yielded by a row iterator, which [`Dedup`] can hash.
+///
+/// Implemented for the items of the row iterators of this crate. Errors are never treated as
+/// duplicates.
+pub trait DedupRow {
+    /// Feeds the row into `state` with [`hash_row()`], or returns `false` if the row should be
+    /// yielded regardless, e.g. because it is an error.
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool;
+}
+
+impl DedupRow for [Cell<'_>] {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        hash_row(self, state);
+        true
+    }
+}
+
+impl<const COLS: usize> DedupRow for [Cell<'_>; COLS] {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        self[..].hash_dedup(state)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DedupRow for Vec<Cell<'_>> {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        self[..].hash_dedup(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl DedupRow for crate::io::Record {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        // Same as `hash_row()`, without collecting the cells into a slice first.
+        for cell in self.iter() {
+            cell.hash_unquoted(state);
+        }
+        true
+    }
+}
+
+impl<R: DedupRow + ?Sized> DedupRow for &R {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        (**self).hash_dedup(state)
+    }
+}
+
+/// Rows yielded along with their byte position range, of which only the cells are hashed.
+impl<R: DedupRow> DedupRow for (R, Range<usize>) {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        self.0.hash_dedup(state)
+    }
+}
+
+impl<R: DedupRow, E> DedupRow for Result<R, E> {
+    fn hash_dedup<H: Hasher>(&self, state: &mut H) -> bool {
+        self.as_ref().is_ok_and(|row| row.hash_dedup(state))
+    }
+}
+
+/// An iterator adapter that skips rows equal to a previously yielded row, ignoring quoting.
+///
+/// `S` builds the hasher used for each row. The default [`RandomState`] hashes differently on each
+/// run, which is fine for deduplicating within one run. See the
+/// [module-level documentation](self) for more details.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct Dedup<I, S = RandomState> {
+    iter: I,
+    hasher: S,
+    seen: HashSet<u64>,
+    duplicates: usize,
+}
+
+#[cfg(feature = "std")]
+impl<I> Dedup<I> {
+    /// Creates an adapter that skips duplicated rows of `iter`.
+    pub fn new(iter: I) -> Self {
+        Self::with_hasher(iter, RandomState::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, S> Dedup<I, S> {
+    /// Creates an adapter that skips duplicated rows of `iter`, hashing them with `hasher`.
+    pub fn with_hasher(iter: I, hasher: S) -> Self {
+        Self {
+            iter,
+            hasher,
+            seen: HashSet::new(),
+            duplicates: 0,
+        }
+    }
+
+    /// Returns the number of rows skipped so far.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+
+    /// Returns the number of distinct rows yielded so far, not counting errors.
+    pub fn distinct(&self) -> usize {
+        self.seen.len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, S> Iterator for Dedup<I, S>
+where
+    I: Iterator<Item: DedupRow>,
+    S: BuildHasher,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let row = self.iter.next()?;
+            let mut state = self.hasher.build_hasher();
+            if !row.hash_dedup(&mut state) || self.seen.insert(state.finish()) {
+                return Some(row);
+            }
+            self.duplicates += 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
@@ -0,0 +1,134 @@
+//! Dequoting many cells into a reusable buffer.
+//!
+//! [`Cell::try_as_str()`] allocates a new [`String`] for every quoted cell, which adds up when
+//! most cells of the input need dequoting. [`DequoteBuf`] instead dequotes all cells of a row into
+//! one contiguous buffer, and returns `&str` views into it. The buffer is cleared and reused for
+//! the next row, so it stops allocating once it has grown to fit the largest row.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{Csv, dequote::DequoteBuf};
+//!
+//! let mut buf = DequoteBuf::new();
+//! for row in Csv::new(b"\"Hi, \"\"Quote\"\"\",\"a\"\n\"b\",c\n").into_rows() {
+//!     let [first, second] = buf.dequote_row(&row?)?;
+//!     println!("{first}: {second}");
+//! }
+//!
+//! // Any number of cells can be dequoted at once, e.g. a whole column.
+//! let column = Csv::new(b"\"x\"\"\"\ny\n\"z\"\n").collect_column(0)?;
+//! let cells: Vec<&str> = buf.dequote(&column)?.collect();
+//! assert_eq!(cells, ["x\"", "y", "z"]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::{iter::FusedIterator, str::Utf8Error};
+
+use crate::Cell;
+
+/// A reusable buffer that cells are dequoted into.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Default, Debug)]
+pub struct DequoteBuf {
+    buf: String,
+    /// End offset of each dequoted cell within `buf`.
+    ends: Vec<usize>,
+}
+
+impl DequoteBuf {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty buffer that can hold at least `bytes` bytes of dequoted content without
+    /// reallocating.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            buf: String::with_capacity(bytes),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Dequotes a row of `COLS` cells, replacing the previous contents of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a cell is not valid UTF-8, as [`Cell::try_as_str()`] does.
+    pub fn dequote_row<const COLS: usize>(
+        &mut self,
+        cells: &[Cell<'_>; COLS],
+    ) -> Result<[&str; COLS], Utf8Error> {
+        let mut cells = self.dequote(cells)?;
+        Ok(core::array::from_fn(|_| cells.next().unwrap_or_default()))
+    }
+
+    /// Dequotes the given cells, replacing the previous contents of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a cell is not valid UTF-8, as [`Cell::try_as_str()`] does.
+    pub fn dequote(&mut self, cells: &[Cell<'_>]) -> Result<DequotedCells<'_>, Utf8Error> {
+        self.buf.clear();
+        self.ends.clear();
+        for cell in cells {
+            core::str::from_utf8(cell.buf)?;
+            // SAFETY: `cell.buf` is valid UTF-8, and the chunks only leave out its surrounding and
+            // escaping quotes. These are ASCII, so every chunk starts and ends at a character
+            // boundary and is valid UTF-8 on its own.
+            let buf = unsafe { self.buf.as_mut_vec() };
+            cell.unquoted_chunks()
+                .for_each(|chunk| buf.extend_from_slice(chunk));
+            self.ends.push(self.buf.len());
+        }
+        Ok(DequotedCells {
+            buf: &self.buf,
+            ends: &self.ends,
+            start: 0,
+        })
+    }
+}
+
+/// An iterator over the cells dequoted into a [`DequoteBuf`].
+///
+/// Can be created by calling [`DequoteBuf::dequote()`].
+#[derive(Clone, Debug)]
+pub struct DequotedCells<'b> {
+    buf: &'b str,
+    ends: &'b [usize],
+    start: usize,
+}
+
+impl<'b> DequotedCells<'b> {
+    /// Returns the remaining cell at `index`, without advancing the iterator.
+    pub fn get(&self, index: usize) -> Option<&'b str> {
+        let end = *self.ends.get(index)?;
+        let start = match index.checked_sub(1) {
+            Some(previous) => self.ends[previous],
+            None => self.start,
+        };
+        Some(&self.buf[start..end])
+    }
+}
+
+impl<'b> Iterator for DequotedCells<'b> {
+    type Item = &'b str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&end, rest) = self.ends.split_first()?;
+        let cell = &self.buf[self.start..end];
+        (self.start, self.ends) = (end, rest);
+        Some(cell)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ends.len(), Some(self.ends.len()))
+    }
+}
+
+impl ExactSizeIterator for DequotedCells<'_> {}
+
+impl FusedIterator for DequotedCells<'_> {}
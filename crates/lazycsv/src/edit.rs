@@ -0,0 +1,241 @@
+//! Lossless editing of CSV buffers.
+//!
+//! [`Editor`] records changes to byte ranges of a buffer, e.g. the ranges yielded by
+//! [`Csv::into_rows_with_cell_range()`](crate::Csv::into_rows_with_cell_range), and writes out the
+//! result by copying every unchanged span verbatim. Quoting and line endings of untouched cells
+//! and rows are therefore preserved exactly.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{Csv, edit::Editor};
+//!
+//! let data = b"isrc,title\r\nUSRC1760783,\"Hi, Quote\"\r\nGBAYE0601498,b\r\n";
+//! let mut editor = Editor::new(data);
+//! for row in Csv::new(data).into_rows_with_cell_range() {
+//!     let ([isrc, _], range) = row?;
+//!     match isrc.cell.buf {
+//!         b"USRC1760783" => editor.replace(isrc.range, b"USRC17607839")?,
+//!         b"GBAYE0601498" => editor.delete(range)?,
+//!         _ => {}
+//!     }
+//! }
+//! editor.append_row([&b"QZ9H71900001"[..], b"new, row"]);
+//! assert_eq!(
+//!     editor.to_vec(),
+//!     b"isrc,title\r\nUSRC17607839,\"Hi, Quote\"\r\nQZ9H71900001,\"new, row\"\r\n",
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use memchr::{memchr, memchr3};
+use thiserror::Error;
+
+/// A set of changes to a CSV buffer.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Editor<'a> {
+    buf: &'a [u8],
+    separator: u8,
+    /// Line break of appended rows, following the first line break of `buf`.
+    line_break: &'static [u8],
+    /// Replacements of non-overlapping ranges, sorted by position.
+    edits: Vec<(Range<usize>, Vec<u8>)>,
+    /// Appended rows, each ending with `line_break`.
+    appended: Vec<u8>,
+}
+
+impl<'a> Editor<'a> {
+    /// Creates a new editor for the given buffer.
+    ///
+    /// To customize the separator character, use [`Editor::with_separator()`].
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self::with_separator(buf, b',')
+    }
+
+    /// Creates a new editor for the given buffer, with the given separator character.
+    pub fn with_separator(buf: &'a [u8], separator: u8) -> Self {
+        let line_break: &[u8] = match memchr(b'\n', buf) {
+            Some(index) if index > 0 && buf[index - 1] == b'\r' => b"\r\n",
+            _ => b"\n",
+        };
+        Self {
+            buf,
+            separator,
+            line_break,
+            edits: Vec::new(),
+            appended: Vec::new(),
+        }
+    }
+
+    /// Replaces the cell at `range` with `value`, quoting it if necessary.
+    ///
+    /// `value` is the unquoted content of the new cell. It's quoted if it contains the separator,
+    /// a quote, or a line break.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range` is out of bounds or overlaps with a previous change.
+    pub fn replace(&mut self, range: Range<usize>, value: &[u8]) -> Result<(), EditError> {
+        let mut raw = Vec::with_capacity(value.len());
+        push_cell(&mut raw, value, self.separator);
+        self.insert(range, raw)
+    }
+
+    /// Replaces the bytes at `range` with `raw` as-is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range` is out of bounds or overlaps with a previous change.
+    pub fn replace_raw(&mut self, range: Range<usize>, raw: &[u8]) -> Result<(), EditError> {
+        self.insert(range, raw.to_vec())
+    }
+
+    /// Deletes the bytes at `range`.
+    ///
+    /// To delete a whole row, pass its range including the line break, e.g. as yielded by
+    /// [`Csv::into_rows_with_range()`](crate::Csv::into_rows_with_range).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range` is out of bounds or overlaps with a previous change.
+    pub fn delete(&mut self, range: Range<usize>) -> Result<(), EditError> {
+        self.insert(range, Vec::new())
+    }
+
+    /// Appends a row to the end of the buffer, quoting each cell if necessary.
+    ///
+    /// The row ends with the same line break as the first row of the buffer. If the buffer
+    /// doesn't end with a line break, one is inserted before the row.
+    pub fn append_row<'v>(&mut self, cells: impl IntoIterator<Item = &'v [u8]>) {
+        for (i, cell) in cells.into_iter().enumerate() {
+            if i != 0 {
+                self.appended.push(self.separator);
+            }
+            push_cell(&mut self.appended, cell, self.separator);
+        }
+        self.appended.extend_from_slice(self.line_break);
+    }
+
+    /// Writes the edited buffer to `out`.
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, mut out: impl Write) -> io::Result<()> {
+        self.chunks().try_for_each(|chunk| out.write_all(chunk))
+    }
+
+    /// Returns the edited buffer.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.buf.len() + self.appended.len());
+        self.chunks().for_each(|chunk| out.extend_from_slice(chunk));
+        out
+    }
+
+    fn insert(&mut self, range: Range<usize>, raw: Vec<u8>) -> Result<(), EditError> {
+        if range.start > range.end || range.end > self.buf.len() {
+            return Err(EditError::OutOfBounds {
+                range,
+                len: self.buf.len(),
+            });
+        }
+
+        let index = self
+            .edits
+            .partition_point(|(existing, _)| existing.start < range.start);
+        let overlapping = [index.checked_sub(1), Some(index)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.edits.get(i))
+            .find(|(existing, _)| {
+                range.start < existing.end && existing.start < range.end
+                    || range.start == existing.start
+            });
+        if let Some((existing, _)) = overlapping {
+            return Err(EditError::Overlap {
+                range,
+                existing: existing.clone(),
+            });
+        }
+
+        self.edits.insert(index, (range, raw));
+        Ok(())
+    }
+
+    /// Returns the last byte of the edited buffer, excluding appended rows.
+    fn last_byte(&self) -> Option<u8> {
+        let mut end = self.buf.len();
+        for (range, raw) in self.edits.iter().rev() {
+            if range.end < end {
+                return Some(self.buf[end - 1]);
+            }
+            if let Some(&c) = raw.last() {
+                return Some(c);
+            }
+            end = range.start;
+        }
+        end.checked_sub(1).map(|i| self.buf[i])
+    }
+
+    /// Returns the edited buffer in chunks.
+    fn chunks(&self) -> impl Iterator<Item = &[u8]> {
+        let mut cursor = 0;
+        let edited = self.edits.iter().flat_map(move |(range, raw)| {
+            let unchanged = &self.buf[cursor..range.start];
+            cursor = range.end;
+            [unchanged, raw]
+        });
+        let rest = &self.buf[self.edits.last().map_or(0, |(range, _)| range.end)..];
+
+        // The appended rows must start on a new line.
+        let line_break: &[u8] = match self.last_byte() {
+            Some(c) if c != b'\n' && !self.appended.is_empty() => self.line_break,
+            _ => &[],
+        };
+
+        edited.chain([rest, line_break, &self.appended])
+    }
+}
+
+/// Pushes `value` as a cell, quoting it if necessary.
+fn push_cell(out: &mut Vec<u8>, value: &[u8], separator: u8) {
+    if memchr3(separator, b'"', b'\n', value).is_none() && memchr(b'\r', value).is_none() {
+        out.extend_from_slice(value);
+        return;
+    }
+
+    out.push(b'"');
+    for (i, part) in value.split(|&c| c == b'"').enumerate() {
+        if i != 0 {
+            out.extend_from_slice(b"\"\"");
+        }
+        out.extend_from_slice(part);
+    }
+    out.push(b'"');
+}
+
+/// Errors returned by [`Editor`].
+#[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EditError {
+    /// The range is not within the buffer.
+    #[error("range {range:?} is out of bounds of the buffer of {len} bytes")]
+    OutOfBounds {
+        /// The range of the change.
+        range: Range<usize>,
+        /// The length of the buffer.
+        len: usize,
+    },
+
+    /// The range overlaps with a previous change.
+    #[error("range {range:?} overlaps with the previously changed range {existing:?}")]
+    Overlap {
+        /// The range of the change.
+        range: Range<usize>,
+        /// The range of the previous change.
+        existing: Range<usize>,
+    },
+}
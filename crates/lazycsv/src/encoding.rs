@@ -0,0 +1,103 @@
+//! Decoding input in legacy encodings, such as CP949 (EUC-KR) and Windows-1252, and UTF-16.
+//!
+//! The parser only looks for ASCII separators, quotes and line breaks, so it works as-is on any
+//! ASCII-compatible encoding whose multi-byte sequences never contain those bytes. This covers
+//! single-byte encodings like Windows-1252 as well as CP949 and EUC-KR. Cells of such input can
+//! be decoded one by one with [`Cell::decode()`], which is cheaper than transcoding the whole
+//! input if only a few cells are needed.
+//!
+//! Shift_JIS, GBK and Big5 also work as-is with separators like `,`, `;` and `\t`. However, the
+//! second byte of their double-byte characters can be any ASCII byte from `@` (0x40) to `~`
+//! (0x7E), so a separator in that range such as `|` may split a cell in the middle of a character.
+//! Transcode such input with [`to_utf8()`] first.
+//!
+//! UTF-16 input has to be transcoded first, as every character takes at least two bytes. Use
+//! [`to_utf8()`] to transcode the whole input, which also detects the encoding from a byte order
+//! mark (BOM) if present.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{
+//!     Csv,
+//!     encoding::{EUC_KR, UTF_16LE, to_utf8},
+//! };
+//!
+//! // "이름,값" in CP949
+//! let data = b"\xc0\xcc\xb8\xa7,\xb0\xaa\n";
+//! let [name, value] = Csv::new(data).into_rows().next().unwrap()?;
+//! assert_eq!(name.decode(EUC_KR)?, "이름");
+//! assert_eq!(value.decode(EUC_KR)?, "값");
+//!
+//! // "a,b" in UTF-16BE with a BOM, which takes precedence over the declared encoding
+//! let data = b"\xfe\xff\x00a\x00,\x00b";
+//! let utf8 = to_utf8(data, UTF_16LE)?;
+//! assert_eq!(utf8, "a,b");
+//! let [a, b] = Csv::new(utf8.as_bytes()).into_rows().next().unwrap()?;
+//! assert_eq!([a.buf, b.buf], [b"a", b"b"]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::borrow::Cow;
+
+pub use encoding_rs::{EUC_KR, Encoding, SHIFT_JIS, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use thiserror::Error;
+
+use crate::Cell;
+
+/// Transcodes the whole input to UTF-8, so that it can be parsed with [`Csv`](crate::Csv).
+///
+/// If the input starts with a BOM, the encoding it indicates is used instead of `encoding`, and
+/// the BOM is stripped. Valid UTF-8 input without a BOM is borrowed as-is if `encoding` is
+/// [`UTF_8`].
+///
+/// # Errors
+///
+/// Returns an error if the input is malformed in the encoding.
+pub fn to_utf8<'a>(
+    buf: &'a [u8],
+    encoding: &'static Encoding,
+) -> Result<Cow<'a, str>, DecodeError> {
+    let (encoding, bom_len) = Encoding::for_bom(buf).unwrap_or((encoding, 0));
+    encoding
+        .decode_without_bom_handling_and_without_replacement(&buf[bom_len..])
+        .ok_or(DecodeError {
+            encoding: encoding.name(),
+        })
+}
+
+impl<'a> Cell<'a> {
+    /// Dequotes the cell and decodes it from `encoding`.
+    ///
+    /// This is the counterpart of [`Cell::try_as_str()`] for input in a legacy encoding. See the
+    /// [`encoding`](crate::encoding) module for the supported encodings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cell is malformed in the encoding.
+    pub fn decode(&self, encoding: &'static Encoding) -> Result<Cow<'a, str>, DecodeError> {
+        let (content, quoted) = match self.buf {
+            [b'"', content @ .., b'"'] => (content, true),
+            buf => (buf, false),
+        };
+        let decoded = encoding
+            .decode_without_bom_handling_and_without_replacement(content)
+            .ok_or(DecodeError {
+                encoding: encoding.name(),
+            })?;
+        Ok(match decoded {
+            decoded if quoted && decoded.contains("\"\"") => {
+                Cow::Owned(decoded.replace("\"\"", "\""))
+            }
+            decoded => decoded,
+        })
+    }
+}
+
+/// Error returned when the input is malformed in the declared encoding.
+#[derive(Error, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[error("input is not valid {encoding}")]
+pub struct DecodeError {
+    /// The name of the encoding.
+    pub encoding: &'static str,
+}
@@ -0,0 +1,179 @@
+//! Parser for fixed-width text files, yielding the same [`Cell`]s as [`Csv`](crate::Csv).
+//!
+//! Each line of a fixed-width file is split into cells by a [`Column`] layout, which gives the
+//! byte offset and width of each column. The rows have the same shape as the ones yielded by
+//! [`CsvRowIter`](crate::CsvRowIter), so code working on rows of cells can accept either format.
+//!
+//! Note that cells of fixed-width files are never quoted. Since [`Cell::try_as_str()`] dequotes
+//! cells that start and end with a quote, use [`core::str::from_utf8()`] on [`Cell::buf`] instead
+//! if a cell may be surrounded by quotes.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use lazycsv::fixed_width::{Column, FixedWidth, Trim};
+//!
+//! let layout = [
+//!     Column { start: 0, width: 12, trim: Trim::None },
+//!     Column { start: 12, width: 10, trim: Trim::End },
+//!     Column { start: 22, width: 5, trim: Trim::Start },
+//! ];
+//! let data = b"USRC17607839Song A        3\nGBAYE0601498Song B      120\n";
+//! for row in FixedWidth::new(data, layout) {
+//!     let [isrc, title, units] = row?;
+//!     println!("{}: {} ({})", isrc.try_as_str()?, title.try_as_str()?, units.try_as_str()?);
+//! }
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use memchr::memchr;
+use thiserror::Error;
+
+use crate::Cell;
+
+/// The position of a column within each line.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Column {
+    /// Byte offset of the column from the beginning of the line.
+    pub start: usize,
+    /// Width of the column in bytes.
+    pub width: usize,
+    /// Which padding spaces to strip from the cell.
+    pub trim: Trim,
+}
+
+/// Padding spaces to strip from the cells of a [`Column`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Trim {
+    /// Keeps the cell as-is.
+    #[default]
+    None,
+    /// Strips leading spaces, e.g. for right-aligned numbers.
+    Start,
+    /// Strips trailing spaces, e.g. for left-aligned text.
+    End,
+    /// Strips both leading and trailing spaces.
+    Both,
+}
+
+/// A stateful fixed-width file parser, yielding each line as a row of cells.
+///
+/// Lines may end with either LF or CRLF. See the [module-level documentation](self) for more
+/// details.
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the layout.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct FixedWidth<'a, const COLS: usize> {
+    buf: &'a [u8],
+    layout: [Column; COLS],
+    pos: usize,
+}
+
+impl<'a, const COLS: usize> FixedWidth<'a, COLS> {
+    /// Creates a new fixed-width parser for the given buffer and column layout.
+    pub fn new(buf: &'a [u8], layout: [Column; COLS]) -> Self {
+        Self {
+            buf,
+            layout,
+            pos: 0,
+        }
+    }
+
+    /// Skips the first `n` lines, e.g. headers.
+    pub fn skip_rows(mut self, n: usize) -> Self {
+        for _ in 0..n {
+            match memchr(b'\n', &self.buf[self.pos..]) {
+                Some(index_relative) => self.pos += index_relative + 1,
+                None => self.pos = self.buf.len(),
+            }
+        }
+        self
+    }
+
+    /// Returns the current byte position of the parser within the input buffer, which is the
+    /// start of the next line.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a, const COLS: usize> Iterator for FixedWidth<'a, COLS> {
+    type Item = Result<[Cell<'a>; COLS], FixedWidthError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.buf[self.pos..];
+        if rest.is_empty() {
+            return None;
+        }
+        let line = match memchr(b'\n', rest) {
+            Some(index) => {
+                self.pos += index + 1;
+                let line = &rest[..index];
+                line.strip_suffix(b"\r").unwrap_or(line)
+            }
+            None => {
+                self.pos = self.buf.len();
+                rest
+            }
+        };
+
+        let mut arr = [Cell { buf: &[] }; COLS];
+        for (i, column) in self.layout.iter().enumerate() {
+            // A width reaching past the end of any line saturates instead of overflowing.
+            let end = column.start.saturating_add(column.width);
+            let buf = match line.get(column.start..end) {
+                Some(buf) => buf,
+                // Trailing spaces are often stripped from lines, which is only harmless if the
+                // column would strip them anyway.
+                None if matches!(column.trim, Trim::End | Trim::Both) => {
+                    line.get(column.start..).unwrap_or_default()
+                }
+                None => {
+                    return Some(Err(FixedWidthError::LineTooShort {
+                        column: i,
+                        expected: end,
+                        actual: line.len(),
+                    }));
+                }
+            };
+            arr[i] = Cell {
+                buf: trim(buf, column.trim),
+            };
+        }
+        Some(Ok(arr))
+    }
+}
+
+fn trim(mut buf: &[u8], trim: Trim) -> &[u8] {
+    if let Trim::Start | Trim::Both = trim {
+        while let [b' ', rest @ ..] = buf {
+            buf = rest;
+        }
+    }
+    if let Trim::End | Trim::Both = trim {
+        while let [rest @ .., b' '] = buf {
+            buf = rest;
+        }
+    }
+    buf
+}
+
+/// Errors returned by [`FixedWidth`].
+#[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FixedWidthError {
+    /// A line ends before one of the columns, which doesn't strip trailing spaces.
+    #[error("column {column} ends at byte {expected}, but the line is {actual} bytes long")]
+    LineTooShort {
+        /// The index of the column.
+        column: usize,
+        /// The end offset of the column.
+        expected: usize,
+        /// The length of the line, excluding its line break.
+        actual: usize,
+    },
+}
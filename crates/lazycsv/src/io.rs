@@ -0,0 +1,550 @@
+//! Streaming record reader for [`std::io::Read`], with transparent decompression.
+//!
+//! [`CsvReader`] reads the input into an internal buffer and splits it with the same parser as
+//! [`Csv`], yielding each complete record as an owned [`Record`]. Records split across reads are
+//! held back until their line break (or the end of the input) arrives.
+//!
+//! [`CsvFollower`] reads input that is being appended to, such as a log-style export, like
+//! `tail -f`. Instead of ending at the end of the input, it waits for more records to arrive.
+//!
+//! [`AutoDecoder`] detects gzip and zstd compressed input by its magic bytes, so that `.csv.gz`
+//! and `.csv.zst` files can be read just like uncompressed ones. Decompression requires the
+//! **gzip** and **zstd** features respectively.
+//!
+//! [`normalize_line_endings()`] rewrites the line breaks between records to either LF or CRLF,
+//! leaving the cells untouched.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::{fs::File, io::BufReader};
+//!
+//! use lazycsv::io::{AutoDecoder, CsvReader};
+//!
+//! let file = File::open("sales.csv.gz")?;
+//! for record in CsvReader::new(AutoDecoder::new(BufReader::new(file))?) {
+//!     let record = record?;
+//!     let [isrc, units] = record.to_array()?;
+//!     println!("{}: {}", isrc.try_as_str()?, units.try_as_str()?);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{ops::Range, time::Duration};
+use std::{
+    fs::File,
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    path::Path,
+    thread,
+};
+
+use memchr::memchr2_iter;
+
+use crate::{Cell, Csv, CsvIterItem, LineEnding, RowIterError};
+
+/// Initial size of the internal buffer, which grows when a record doesn't fit in it.
+const INITIAL_CAPACITY: usize = 8 * 1024;
+
+/// Default time [`CsvFollower`] waits before checking for appended data again.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A blocking stream of records read from [`Read`].
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug)]
+pub struct CsvReader<R> {
+    reader: R,
+    buf: RecordBuffer,
+}
+
+impl<R: Read> CsvReader<R> {
+    /// Creates a new record stream reading from `reader`.
+    ///
+    /// To customize the separator character, use [`CsvReader::with_separator()`].
+    pub fn new(reader: R) -> Self {
+        Self::with_separator(reader, b',')
+    }
+
+    /// Creates a new record stream reading from `reader`, with the given separator character.
+    pub fn with_separator(reader: R, separator: u8) -> Self {
+        Self {
+            reader,
+            buf: RecordBuffer::new(separator),
+        }
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Data that has been read but not yielded as records yet is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for CsvReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.buf.parse_record() {
+                return Some(record);
+            }
+            if self.buf.eof {
+                return None;
+            }
+
+            match self.reader.read(self.buf.spare()) {
+                Ok(n) => self.buf.fill(n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// A blocking stream of records read from input that is being appended to, like `tail -f`.
+///
+/// Complete records are yielded as soon as they are read. A trailing record without a line break
+/// is held back until its line break is appended, as the record might still continue. Reaching
+/// the end of the input doesn't end the iterator, which instead waits for more data, checking
+/// every [interval](CsvFollower::with_interval). Use [`CsvFollower::try_next()`] to check for new
+/// records without waiting.
+///
+/// [`CsvFollower::position()`] returns the position right after the last yielded record. It can be
+/// stored to resume following with [`CsvFollower::open_at()`] later, without yielding the same
+/// records twice or skipping a partially written one.
+///
+/// Truncating or replacing the file, e.g. by log rotation, isn't detected. Open the file again in
+/// that case.
+///
+/// # Example
+///
+/// ```no_run
+/// use lazycsv::io::CsvFollower;
+///
+/// # fn load_position() -> usize { 0 }
+/// # fn save_position(_: usize) {}
+/// let mut follower = CsvFollower::open_at("events.csv", load_position())?;
+/// while let Some(record) = follower.next() {
+///     let record = record?;
+///     let [time, event] = record.to_array()?;
+///     println!("{}: {}", time.try_as_str()?, event.try_as_str()?);
+///     save_position(follower.position());
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct CsvFollower<R = File> {
+    reader: R,
+    buf: RecordBuffer,
+    interval: Duration,
+}
+
+impl CsvFollower<File> {
+    /// Opens the file at `path` and follows it from the start.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open_at(path, 0)
+    }
+
+    /// Opens the file at `path` and follows it from `position`, which should be the start of a
+    /// record, e.g. a position previously returned by [`CsvFollower::position()`].
+    pub fn open_at(path: impl AsRef<Path>, position: usize) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(position as u64))?;
+        let mut follower = Self::new(file);
+        follower.buf.offset = position;
+        Ok(follower)
+    }
+}
+
+impl<R: Read> CsvFollower<R> {
+    /// Creates a new record stream following `reader` from its current position.
+    ///
+    /// `reader` should keep returning newly appended data after it once reported the end of the
+    /// input, as [`File`] does. To customize the separator character, use
+    /// [`CsvFollower::with_separator()`].
+    pub fn new(reader: R) -> Self {
+        Self::with_separator(reader, b',')
+    }
+
+    /// Creates a new record stream following `reader`, with the given separator character.
+    pub fn with_separator(reader: R, separator: u8) -> Self {
+        Self {
+            reader,
+            buf: RecordBuffer::new(separator),
+            interval: FOLLOW_INTERVAL,
+        }
+    }
+
+    /// Sets how long the iterator waits before checking for appended data again. Defaults to
+    /// 250 milliseconds.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the next complete record, or `None` if no complete record has been appended yet.
+    ///
+    /// Unlike [`Iterator::next()`], this doesn't wait for more data.
+    pub fn try_next(&mut self) -> Option<io::Result<Record>> {
+        loop {
+            if let Some(record) = self.buf.parse_record() {
+                return Some(record);
+            }
+
+            match self.reader.read(self.buf.spare()) {
+                Ok(0) => return None,
+                Ok(n) => self.buf.fill(n),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    /// Returns the byte position right after the last yielded record.
+    ///
+    /// Data past this position, including a held back partial record, is read again when resuming
+    /// from it.
+    pub fn position(&self) -> usize {
+        self.buf.position()
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Data that has been read but not yielded as records yet is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for CsvFollower<R> {
+    type Item = io::Result<Record>;
+
+    /// Returns the next record, waiting for it to be appended if necessary. Never returns `None`.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.try_next() {
+                return Some(record);
+            }
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+/// Buffered input shared by the record readers.
+#[derive(Debug)]
+pub(crate) struct RecordBuffer {
+    separator: u8,
+    buf: Vec<u8>,
+    /// Start of the unconsumed data in `buf`.
+    start: usize,
+    /// End of the data read into `buf`.
+    filled: usize,
+    /// Position of `buf[0]` in the whole input.
+    offset: usize,
+    /// Length of the pending data after `start` already scanned for the end of the record.
+    scanned: usize,
+    /// Whether the scanned data ends inside quotes.
+    in_quoted_state: bool,
+    pub(crate) eof: bool,
+}
+
+impl RecordBuffer {
+    pub(crate) fn new(separator: u8) -> Self {
+        Self {
+            separator,
+            buf: vec![0; INITIAL_CAPACITY],
+            start: 0,
+            filled: 0,
+            offset: 0,
+            scanned: 0,
+            in_quoted_state: false,
+            eof: false,
+        }
+    }
+
+    /// Splits the next record off the buffered data, if it's complete.
+    pub(crate) fn parse_record(&mut self) -> Option<io::Result<Record>> {
+        // Only tokenize the record once it's complete. Scanning resumes where the last call
+        // stopped, so a long record arriving in small reads is still scanned in linear time.
+        if !self.eof && !self.scan_line_break() {
+            return None;
+        }
+
+        let data = &self.buf[self.start..self.filled];
+        let mut csv = Csv::with_separator(data, self.separator);
+        let mut cells = Vec::new();
+        let end = loop {
+            let position = csv.position();
+            match csv.next() {
+                Some(CsvIterItem::Cell(cell)) => cells.push(position..position + cell.buf.len()),
+                Some(CsvIterItem::LineEnd) => break csv.position(),
+                // The last record doesn't need a line break at the end of the input.
+                None if self.eof && position == data.len() && !cells.is_empty() => {
+                    break data.len();
+                }
+                None if self.eof && position != data.len() => {
+                    // Discard the rest, so that the stream ends after the error.
+                    self.start = self.filled;
+                    return Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input ended inside a quoted cell",
+                    )));
+                }
+                // Wait for more data, as the record might continue.
+                None => return None,
+            }
+        };
+
+        let record = Record {
+            buf: data[..end].to_vec(),
+            cells,
+            position: self.offset + self.start,
+        };
+        self.start += end;
+        self.scanned = 0;
+        self.in_quoted_state = false;
+        Some(Ok(record))
+    }
+
+    /// Returns `true` if the pending data contains a line break outside quotes, continuing the
+    /// scan from where the last call stopped.
+    fn scan_line_break(&mut self) -> bool {
+        let data = &self.buf[self.start..self.filled];
+        for index in memchr2_iter(b'\n', b'"', &data[self.scanned..]) {
+            if data[self.scanned + index] == b'"' {
+                self.in_quoted_state = !self.in_quoted_state;
+            } else if !self.in_quoted_state {
+                return true;
+            }
+        }
+        self.scanned = data.len();
+        false
+    }
+
+    /// Returns the position of the unconsumed data in the whole input.
+    pub(crate) fn position(&self) -> usize {
+        self.offset + self.start
+    }
+
+    /// Returns the space to read more data into, either by discarding consumed data or by
+    /// growing the buffer.
+    pub(crate) fn spare(&mut self) -> &mut [u8] {
+        if self.start != 0 {
+            self.buf.copy_within(self.start..self.filled, 0);
+            self.offset += self.start;
+            self.filled -= self.start;
+            self.start = 0;
+        }
+        if self.filled == self.buf.len() {
+            self.buf.resize(self.buf.len() * 2, 0);
+        }
+        &mut self.buf[self.filled..]
+    }
+
+    /// Marks `n` bytes of the spare space as read, where zero means the end of the input.
+    pub(crate) fn fill(&mut self, n: usize) {
+        match n {
+            0 => self.eof = true,
+            n => self.filled += n,
+        }
+    }
+}
+
+/// An owned record yielded by [`CsvReader`], [`CsvFollower`] and the `tokio` module's
+/// `AsyncCsvReader`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Record {
+    /// The raw record, including its line break.
+    buf: Vec<u8>,
+    cells: Vec<Range<usize>>,
+    position: usize,
+}
+
+impl Record {
+    /// Returns the number of cells in the record.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if the record has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the cell at the given index.
+    pub fn get(&self, index: usize) -> Option<Cell<'_>> {
+        let range = self.cells.get(index)?;
+        Some(Cell {
+            buf: &self.buf[range.clone()],
+        })
+    }
+
+    /// Returns an iterator over the cells in the record.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Cell<'_>> {
+        self.cells.iter().map(|range| Cell {
+            buf: &self.buf[range.clone()],
+        })
+    }
+
+    /// Returns the cells as an array, checking that the record has exactly `COLS` cells.
+    pub fn to_array<const COLS: usize>(&self) -> Result<[Cell<'_>; COLS], RowIterError> {
+        if self.cells.len() < COLS {
+            return Err(RowIterError::ColumnCountSmallerThanExpected {
+                expected: COLS,
+                actual: self.cells.len(),
+            });
+        }
+        if self.cells.len() > COLS {
+            return Err(RowIterError::ColumnCountLargerThanExpected { expected: COLS });
+        }
+        Ok(core::array::from_fn(|i| Cell {
+            buf: &self.buf[self.cells[i].clone()],
+        }))
+    }
+
+    /// Returns the raw bytes of the record, including its line break.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the byte position of the record within the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Writes the remaining rows of `csv` to `out`, terminating every row with `line_ending`.
+///
+/// Everything other than the line breaks between rows is copied verbatim, including line breaks
+/// inside quoted cells. A last row without a line break is left without one.
+///
+/// # Example
+///
+/// ```
+/// use lazycsv::{Csv, LineEnding, io::normalize_line_endings};
+///
+/// let mut out = Vec::new();
+/// let csv = Csv::new(b"a,\"b\r\nc\"\n1,2\r\n3,4");
+/// normalize_line_endings(csv, &mut out, LineEnding::Crlf)?;
+/// assert_eq!(out, b"a,\"b\r\nc\"\r\n1,2\r\n3,4");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn normalize_line_endings(
+    csv: Csv<'_>,
+    mut out: impl Write,
+    line_ending: LineEnding,
+) -> io::Result<()> {
+    let buf = csv.buf;
+    let mut cursor = csv.position();
+    for (item, range) in csv.into_items_with_range() {
+        if item == CsvIterItem::LineEnd {
+            out.write_all(&buf[cursor..range.start])?;
+            out.write_all(line_ending.as_bytes())?;
+            cursor = range.end;
+        }
+    }
+    out.write_all(&buf[cursor..])
+}
+
+/// Compression format of an input, detected by its magic bytes.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Compression {
+    /// Not compressed, or compressed in an unknown format.
+    None,
+    /// gzip, starting with `1f 8b`.
+    Gzip,
+    /// Zstandard, starting with `28 b5 2f fd`.
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression format from the first bytes of an input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::io::Compression;
+    ///
+    /// assert_eq!(Compression::detect(b"\x1f\x8b\x08\x00"), Compression::Gzip);
+    /// assert_eq!(Compression::detect(b"a,b,c\n"), Compression::None);
+    /// ```
+    pub fn detect(buf: &[u8]) -> Self {
+        if buf.starts_with(b"\x1f\x8b") {
+            Compression::Gzip
+        } else if buf.starts_with(b"\x28\xb5\x2f\xfd") {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// A reader that transparently decompresses gzip or zstd compressed input.
+///
+/// The compression format is detected by the magic bytes at the beginning of the input, and
+/// uncompressed input is passed through as-is.
+pub enum AutoDecoder<R: BufRead> {
+    /// Uncompressed input.
+    Plain(R),
+    /// gzip compressed input, possibly consisting of multiple members.
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::bufread::MultiGzDecoder<R>),
+    /// Zstandard compressed input.
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, R>),
+}
+
+impl<R: BufRead> AutoDecoder<R> {
+    /// Creates a reader that decompresses `reader` if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`io::ErrorKind::Unsupported`] if the input is compressed in a format
+    /// whose feature is disabled, or any error returned while reading the magic bytes.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let compression = Compression::detect(reader.fill_buf()?);
+        match compression {
+            Compression::None => Ok(AutoDecoder::Plain(reader)),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(AutoDecoder::Gzip(flate2::bufread::MultiGzDecoder::new(
+                reader,
+            ))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(AutoDecoder::Zstd(zstd::stream::read::Decoder::with_buffer(
+                reader,
+            )?)),
+            #[allow(unreachable_patterns)]
+            compression => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                alloc::format!(
+                    "{compression:?} compressed input requires the corresponding feature"
+                ),
+            )),
+        }
+    }
+
+    /// Returns the detected compression format.
+    pub fn compression(&self) -> Compression {
+        match self {
+            AutoDecoder::Plain(_) => Compression::None,
+            #[cfg(feature = "gzip")]
+            AutoDecoder::Gzip(_) => Compression::Gzip,
+            #[cfg(feature = "zstd")]
+            AutoDecoder::Zstd(_) => Compression::Zstd,
+        }
+    }
+}
+
+impl<R: BufRead> Read for AutoDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            AutoDecoder::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "gzip")]
+            AutoDecoder::Gzip(reader) => reader.read(buf),
+            #[cfg(feature = "zstd")]
+            AutoDecoder::Zstd(reader) => reader.read(buf),
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Conversion from CSV to [JSON Lines](https://jsonlines.org) or JSON.
+//!
+//! The first record is used as the keys of the JSON objects. Cells are dequoted and JSON-escaped
+//! directly into the output writer, so no intermediate strings are allocated per cell.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{
+//!     Csv,
+//!     json::{JsonOptions, write_json},
+//! };
+//!
+//! let mut out = Vec::new();
+//! let csv = Csv::new(b"isrc,title,units\nUSRC17607839,\"Hi \"\"Quote\"\"\",3\n");
+//! let options = JsonOptions {
+//!     infer_types: true,
+//!     ..Default::default()
+//! };
+//! assert_eq!(write_json(csv, &mut out, options)?, 1);
+//! assert_eq!(
+//!     out,
+//!     br#"{"isrc":"USRC17607839","title":"Hi \"Quote\"","units":3}
+//! "#,
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::{vec, vec::Vec};
+use std::io::{self, Write};
+
+use thiserror::Error;
+
+use crate::{Cell, Csv, CsvIterItem, LineEnding, RowIterError};
+
+/// Output format of [`write_json()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum JsonFormat {
+    /// One JSON object per line, also known as NDJSON.
+    #[default]
+    Lines,
+    /// A single JSON array containing every object.
+    Array,
+}
+
+/// Options for [`write_json()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct JsonOptions {
+    /// The output format.
+    pub format: JsonFormat,
+    /// Emits numbers and booleans unquoted in columns where every non-empty cell is a JSON
+    /// number, or every non-empty cell is `true` or `false` (case-insensitively). Empty cells in
+    /// such columns become `null`.
+    ///
+    /// Inferring column types requires an extra pass over the buffer before writing.
+    pub infer_types: bool,
+    /// The line break written after each line of the output.
+    pub line_ending: LineEnding,
+}
+
+/// Errors returned by [`write_json()`].
+#[derive(Error, Debug)]
+pub enum JsonError {
+    /// Failed to write to the output.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// A cell is not valid UTF-8.
+    #[error("cell at byte {position} is not valid UTF-8")]
+    InvalidUtf8 {
+        /// Byte position of the cell.
+        position: usize,
+    },
+
+    /// The header row contains the same name more than once, which would produce duplicate keys.
+    #[error("header at byte {position} is a duplicate")]
+    DuplicateHeader {
+        /// Byte position of the duplicate header cell.
+        position: usize,
+    },
+
+    /// A record has a different number of cells than the header row.
+    #[error("record at byte {position}: {source}")]
+    Row {
+        /// Byte position of the record.
+        position: usize,
+        /// The column count mismatch.
+        #[source]
+        source: RowIterError,
+    },
+}
+
+/// Writes the records of `csv` as JSON objects keyed by the header row, returning the number of
+/// records written.
+///
+/// The header row is the first record from the current position of `csv`, so call
+/// [`Csv::skip_rows()`] beforehand to skip preambles. Header names must be unique, as duplicate
+/// keys in a JSON object usually make readers keep only the last value.
+///
+/// On error, the output may contain a partially written record.
+pub fn write_json(
+    csv: Csv<'_>,
+    mut out: impl Write,
+    options: JsonOptions,
+) -> Result<usize, JsonError> {
+    let mut keys = Vec::new();
+    let mut items = csv.into_items_with_range();
+    while let Some((CsvIterItem::Cell(cell), range)) = items.next() {
+        // Render each key once, as `"key":`.
+        let mut key = Vec::new();
+        write_str(&mut key, cell, range.start)?;
+        key.push(b':');
+        // Compare the rendered keys, so headers that only differ in quoting are duplicates too.
+        if keys.contains(&key) {
+            return Err(JsonError::DuplicateHeader {
+                position: range.start,
+            });
+        }
+        keys.push(key);
+    }
+
+    let mut csv = items.into_inner();
+    let types = if options.infer_types {
+        infer_types(csv, keys.len())
+    } else {
+        vec![ColumnType::String; keys.len()]
+    };
+
+    if options.format == JsonFormat::Array {
+        out.write_all(b"[")?;
+    }
+
+    let line_ending = options.line_ending.as_bytes();
+    let mut count = 0;
+    let mut column = 0;
+    let mut record_position = csv.position();
+    loop {
+        let position = csv.position();
+        match csv.next() {
+            Some(CsvIterItem::Cell(cell)) => {
+                if column == 0 {
+                    record_position = position;
+                    if options.format == JsonFormat::Array {
+                        if count != 0 {
+                            out.write_all(b",")?;
+                        }
+                        out.write_all(line_ending)?;
+                    }
+                    out.write_all(b"{")?;
+                } else {
+                    out.write_all(b",")?;
+                }
+
+                let Some(key) = keys.get(column) else {
+                    return Err(JsonError::Row {
+                        position: record_position,
+                        source: RowIterError::ColumnCountLargerThanExpected {
+                            expected: keys.len(),
+                        },
+                    });
+                };
+                out.write_all(key)?;
+                match types[column] {
+                    ColumnType::Number | ColumnType::Boolean if is_empty(cell) => {
+                        out.write_all(b"null")?;
+                    }
+                    ColumnType::Number => out.write_all(unquote(cell).0)?,
+                    ColumnType::Boolean if unquote(cell).0.eq_ignore_ascii_case(b"true") => {
+                        out.write_all(b"true")?;
+                    }
+                    ColumnType::Boolean => out.write_all(b"false")?,
+                    ColumnType::Unknown | ColumnType::String => {
+                        write_str(&mut out, cell, position)?;
+                    }
+                }
+                column += 1;
+            }
+            item @ (Some(CsvIterItem::LineEnd) | None) if column != 0 => {
+                if column < keys.len() {
+                    return Err(JsonError::Row {
+                        position: record_position,
+                        source: RowIterError::ColumnCountSmallerThanExpected {
+                            expected: keys.len(),
+                            actual: column,
+                        },
+                    });
+                }
+                out.write_all(b"}")?;
+                if options.format == JsonFormat::Lines {
+                    out.write_all(line_ending)?;
+                }
+                count += 1;
+                column = 0;
+                if item.is_none() {
+                    break;
+                }
+            }
+            Some(CsvIterItem::LineEnd) => {}
+            None => break,
+        }
+    }
+
+    if options.format == JsonFormat::Array {
+        if count != 0 {
+            out.write_all(line_ending)?;
+        }
+        out.write_all(b"]")?;
+        out.write_all(line_ending)?;
+    }
+    Ok(count)
+}
+
+/// Inferred type of a column, ordered from the most specific.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum ColumnType {
+    /// Only empty cells have been seen so far.
+    Unknown,
+    Number,
+    Boolean,
+    String,
+}
+
+impl ColumnType {
+    fn of(cell: Cell<'_>) -> Self {
+        let (content, _) = unquote(cell);
+        if content.is_empty() {
+            ColumnType::Unknown
+        } else if is_json_number(content) {
+            ColumnType::Number
+        } else if content.eq_ignore_ascii_case(b"true") || content.eq_ignore_ascii_case(b"false") {
+            ColumnType::Boolean
+        } else {
+            ColumnType::String
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (ColumnType::Unknown, other) | (other, ColumnType::Unknown) => other,
+            (a, b) if a == b => a,
+            _ => ColumnType::String,
+        }
+    }
+}
+
+fn infer_types(csv: Csv<'_>, columns: usize) -> Vec<ColumnType> {
+    let mut types = vec![ColumnType::Unknown; columns];
+    let mut column = 0;
+    for item in csv {
+        match item {
+            CsvIterItem::Cell(cell) => {
+                if let Some(ty) = types.get_mut(column) {
+                    *ty = ty.merge(ColumnType::of(cell));
+                }
+                column += 1;
+            }
+            CsvIterItem::LineEnd => column = 0,
+        }
+    }
+    types
+}
+
+/// Strips the surrounding quotes, returning whether the cell was quoted.
+fn unquote(cell: Cell<'_>) -> (&[u8], bool) {
+    match cell.buf {
+        [b'"', content @ .., b'"'] => (content, true),
+        buf => (buf, false),
+    }
+}
+
+fn is_empty(cell: Cell<'_>) -> bool {
+    unquote(cell).0.is_empty()
+}
+
+/// Checks whether `s` matches the number grammar of [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259#section-6).
+fn is_json_number(s: &[u8]) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|c| c.is_ascii_digit()).count()
+    }
+
+    let mut i = (s.first() == Some(&b'-')) as usize;
+    match digits(&s[i..]) {
+        0 => return false,
+        n if n > 1 && s[i] == b'0' => return false,
+        n => i += n,
+    }
+    if s.get(i) == Some(&b'.') {
+        match digits(&s[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    if let Some(b'e' | b'E') = s.get(i) {
+        i += 1;
+        if let Some(b'+' | b'-') = s.get(i) {
+            i += 1;
+        }
+        match digits(&s[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == s.len()
+}
+
+/// Writes the cell starting at `position` as a JSON string, dequoting and escaping on the fly.
+fn write_str(out: &mut impl Write, cell: Cell<'_>, position: usize) -> Result<(), JsonError> {
+    let (content, quoted) = unquote(cell);
+    if core::str::from_utf8(content).is_err() {
+        return Err(JsonError::InvalidUtf8 { position });
+    }
+
+    out.write_all(b"\"")?;
+    let mut start = 0;
+    let mut i = 0;
+    while i < content.len() {
+        let c = content[i];
+        if c != b'"' && c != b'\\' && c >= 0x20 {
+            i += 1;
+            continue;
+        }
+
+        out.write_all(&content[start..i])?;
+        match c {
+            b'"' => {
+                out.write_all(b"\\\"")?;
+                // Collapse the escaped quote (`""`) in a quoted cell, but keep a stray one as is.
+                if quoted && content.get(i + 1) == Some(&b'"') {
+                    i += 1;
+                }
+            }
+            b'\\' => out.write_all(b"\\\\")?,
+            b'\n' => out.write_all(b"\\n")?,
+            b'\r' => out.write_all(b"\\r")?,
+            b'\t' => out.write_all(b"\\t")?,
+            _ => write!(out, "\\u{c:04x}")?,
+        }
+        i += 1;
+        start = i;
+    }
+    out.write_all(&content[start..])?;
+    out.write_all(b"\"")?;
+    Ok(())
+}
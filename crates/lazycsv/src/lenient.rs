@@ -0,0 +1,213 @@
+//! Lenient row iteration, which skips malformed rows and reports them.
+//!
+//! [`LenientRowIter`] yields the same rows as [`CsvRowWithRangeIter`](crate::CsvRowWithRangeIter),
+//! but instead of failing on a malformed row, it records the row as a [`SkippedRow`] and
+//! continues with the next one. A row is malformed if:
+//!
+//! - it has a different number of cells than expected, or
+//! - one of its cells has broken quoting, as checked by [`Cell::validate_quoting()`], or
+//! - a quote in it is never closed.
+//!
+//! Since a stray quote makes the parser read past the end of the line, rows with broken quoting
+//! are skipped up to the first line break after the offending cell, and parsing resumes from
+//! there.
+//!
+//! At most `max_errors` rows are skipped. The row exceeding the budget is reported as well, and
+//! the iteration stops with [`ErrorBudgetExceeded`].
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{Csv, RowIterError, lenient::SkipReason};
+//!
+//! let mut rows = Csv::new(b"a,b\n1,2,3\n4,\"5\"x\n6,7\n").into_rows_lenient::<2>(2);
+//! let rows_read: Vec<_> = rows.by_ref().map(|row| row.map(|(_, range)| range)).collect();
+//! assert_eq!(rows_read, [Ok(0..4), Ok(17..21)]);
+//!
+//! let skipped = rows.skipped();
+//! assert_eq!(skipped[0].range, 4..10);
+//! assert_eq!(
+//!     skipped[0].reason,
+//!     SkipReason::ColumnCount(RowIterError::ColumnCountLargerThanExpected { expected: 2 }),
+//! );
+//! assert_eq!(skipped[1].range, 10..17);
+//! ```
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use memchr::memchr;
+use thiserror::Error;
+
+use crate::{Cell, Csv, CsvIterItem, IterState, QuoteError, RowIterError};
+
+/// An iterator that yields well-formed rows along with byte position range, skipping the others.
+///
+/// Can be created by calling [`Csv::into_rows_lenient()`]. See the
+/// [module-level documentation](self) for more details.
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the CSV.
+#[derive(Clone, Debug)]
+pub struct LenientRowIter<'a, const COLS: usize> {
+    csv: Csv<'a>,
+    max_errors: usize,
+    skipped: Vec<SkippedRow>,
+}
+
+impl<'a, const COLS: usize> LenientRowIter<'a, COLS> {
+    pub(crate) fn new(csv: Csv<'a>, max_errors: usize) -> Self {
+        Self {
+            csv,
+            max_errors,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Returns the rows skipped so far.
+    pub fn skipped(&self) -> &[SkippedRow] {
+        &self.skipped
+    }
+
+    /// Returns the rows skipped so far, consuming the iterator.
+    pub fn into_skipped(self) -> Vec<SkippedRow> {
+        self.skipped
+    }
+
+    /// Reads the next row, returning either its cells or the row to skip.
+    ///
+    /// Returns `None` if there are no more rows.
+    #[allow(clippy::type_complexity)]
+    fn next_row(&mut self) -> Option<Result<([Cell<'a>; COLS], Range<usize>), SkippedRow>> {
+        let buf = self.csv.buf;
+        let start = match self.csv.state {
+            IterState::Cell(pos) => pos,
+            IterState::LineEnd(pos, is_crlf) => pos + 1 + (is_crlf as usize),
+            IterState::Done => return None,
+        };
+        self.csv.state = IterState::Cell(start);
+
+        let mut arr = [Cell { buf: &[] }; COLS];
+        let mut count = 0;
+        // The first cell with broken quoting, and its position.
+        let mut malformed = None;
+        let end = loop {
+            let position = self.csv.position();
+            match self.csv.next() {
+                Some(CsvIterItem::Cell(cell)) => {
+                    if malformed.is_none()
+                        && let Err(source) = cell.validate_quoting()
+                    {
+                        let reason = SkipReason::Quoting {
+                            column: count,
+                            source,
+                        };
+                        malformed = Some((reason, position));
+                    }
+                    if let Some(slot) = arr.get_mut(count) {
+                        *slot = cell;
+                    }
+                    count += 1;
+                }
+                Some(CsvIterItem::LineEnd) => break self.csv.position(),
+                // The parser only gives up on remaining data when a quote is left open.
+                None if position < buf.len() => {
+                    if malformed.is_none() {
+                        malformed = Some((SkipReason::UnterminatedQuote, position));
+                    }
+                    break buf.len();
+                }
+                None if count == 0 => return None,
+                None => break buf.len(),
+            }
+        };
+
+        if let Some((reason, position)) = malformed {
+            // Resynchronize at the line break after the offending cell.
+            let end = memchr(b'\n', &buf[position..]).map_or(buf.len(), |i| position + i + 1);
+            self.csv.state = if end < buf.len() {
+                IterState::Cell(end)
+            } else {
+                IterState::Done
+            };
+            return Some(Err(SkippedRow {
+                range: start..end,
+                reason,
+            }));
+        }
+
+        let error = match count {
+            count if count < COLS => RowIterError::ColumnCountSmallerThanExpected {
+                expected: COLS,
+                actual: count,
+            },
+            count if count > COLS => RowIterError::ColumnCountLargerThanExpected { expected: COLS },
+            _ => return Some(Ok((arr, start..end))),
+        };
+        Some(Err(SkippedRow {
+            range: start..end,
+            reason: SkipReason::ColumnCount(error),
+        }))
+    }
+}
+
+impl<'a, const COLS: usize> Iterator for LenientRowIter<'a, COLS> {
+    type Item = Result<([Cell<'a>; COLS], Range<usize>), ErrorBudgetExceeded>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_row()? {
+                Ok(row) => return Some(Ok(row)),
+                Err(skipped) => {
+                    self.skipped.push(skipped);
+                    if self.skipped.len() > self.max_errors {
+                        self.csv.state = IterState::Done;
+                        return Some(Err(ErrorBudgetExceeded {
+                            max_errors: self.max_errors,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A malformed row skipped by [`LenientRowIter`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SkippedRow {
+    /// The byte position range of the row, including its line break.
+    pub range: Range<usize>,
+    /// Why the row was skipped.
+    pub reason: SkipReason,
+}
+
+/// The reason a row was skipped by [`LenientRowIter`].
+#[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SkipReason {
+    /// The row has a different number of cells than expected.
+    #[error(transparent)]
+    ColumnCount(RowIterError),
+
+    /// A cell of the row has broken quoting.
+    #[error("column {column}: {source}")]
+    Quoting {
+        /// The index of the cell.
+        column: usize,
+        /// The quoting problem.
+        #[source]
+        source: QuoteError,
+    },
+
+    /// A quote in the row is never closed.
+    #[error("quoted cell is never closed")]
+    UnterminatedQuote,
+}
+
+/// Error returned by [`LenientRowIter`] when more rows than allowed are malformed.
+#[derive(Error, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[error("more than {max_errors} malformed rows")]
+pub struct ErrorBudgetExceeded {
+    /// The maximum number of rows that may be skipped.
+    pub max_errors: usize,
+}
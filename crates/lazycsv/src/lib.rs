@@ -76,31 +76,79 @@
 //! # Crate features
 //!
 //! * **std** - When enabled (the default), this will permit features specific to the standard
-//!   library. Currently, the only thing used from the standard library is runtime SIMD CPU feature
+//!   library. Most importantly, the standard library is used for runtime SIMD CPU feature
 //!   detection. This means that this feature must be enabled to get AVX2 accelerated routines on
 //!   `x86_64` targets without enabling the `avx2` feature at compile time, for example. When `std`
 //!   is not enabled, this crate will still attempt to use SSE2 accelerated routines on `x86_64`.
 //!   It will also use AVX2 accelerated routines when the `avx2` feature is enabled at compile
-//!   time. In general, enable this feature if you can.
+//!   time. In general, enable this feature if you can. This feature also enables the [`io`] and
+//!   [`json`] modules, which read and write through [`std::io`], and [`dedup::Dedup`].
 //! * **alloc** - When enabled (the default), API in this crate requiring some kind of allocation
-//!   will become available. (i.e. [`Cell::try_as_str`](crate::Cell::try_as_str)) Otherwise, this
-//!   crate is designed from the ground up to be usable in core-only contexts, so the `alloc`
-//!   feature doesn't add much currently. Notably, disabling `std` but enabling `alloc` will
-//!   **not** result in the use of AVX2 on `x86_64` targets unless the `avx2` feature is enabled at
-//!   compile time. (With `std` enabled, AVX2 can be used even without the `avx2` feature enabled
-//!   at compile time by way of runtime CPU feature detection.)
+//!   will become available. (e.g. [`Cell::try_as_str`](crate::Cell::try_as_str),
+//!   [`Csv::collect_column`] and the [`dequote`], [`edit`], [`lenient`] and [`owned`] modules.)
+//!   Parsing itself never allocates, so the rest of this crate stays usable in core-only contexts.
+//!   Notably, disabling `std` but enabling `alloc` will **not** result in the use of AVX2 on
+//!   `x86_64` targets unless the `avx2` feature is enabled at compile time. (With `std` enabled,
+//!   AVX2 can be used even without the `avx2` feature enabled at compile time by way of runtime
+//!   CPU feature detection.)
+//! * **derive** - Enables `#[derive(FromRow)]` for parsing rows into structs with
+//!   [`typed::FromRow`].
+//! * **csv** - Enables the `csv` module, which converts rows into the record types of the
+//!   [`csv`](https://docs.rs/csv) crate. Implies **std**.
+//! * **encoding** - Enables the `encoding` module and `Cell::decode()`, which decode input
+//!   in legacy encodings such as CP949 and Windows-1252, or UTF-16. Implies **alloc**.
+//! * **gzip** - Enables decompressing gzip input with [`io::AutoDecoder`]. Implies **std**.
+//! * **zstd** - Enables decompressing Zstandard input with [`io::AutoDecoder`]. Implies **std**.
+//! * **tokio** - Enables the `tokio` module, which provides an asynchronous record stream for
+//!   `tokio::io::AsyncRead`. Implies **std**.
 
 #![no_std]
 #![deny(missing_docs)]
 
-use core::{hash::Hash, mem::MaybeUninit, ops::Range};
+use core::{
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem::MaybeUninit,
+    ops::Range,
+};
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 #[cfg(feature = "alloc")]
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod dedup;
+#[cfg(feature = "alloc")]
+pub mod dequote;
+#[cfg(feature = "alloc")]
+pub mod edit;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod fixed_width;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "alloc")]
+pub mod lenient;
+pub mod limit;
+#[cfg(feature = "alloc")]
+pub mod owned;
+pub mod push;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+pub mod typed;
 
-use memchr::{memchr, memchr3};
+use memchr::{
+    memchr, memchr_iter, memchr2, memchr2_iter, memchr3, memchr3_iter, memmem, memrchr, memrchr2,
+    memrchr3,
+};
 use thiserror::Error;
 
 /// A stateful CSV parser.
@@ -111,6 +159,9 @@ pub struct Csv<'a> {
     buf: &'a [u8],
     separator: u8,
     state: IterState,
+    /// Whether the end of `buf` is known to be followed by a cell, even if it's empty.
+    /// Set by [`DoubleEndedIterator::next_back()`] after consuming a separator or a line break.
+    back_cell: bool,
 }
 
 impl<'a> Csv<'a> {
@@ -130,6 +181,7 @@ impl<'a> Csv<'a> {
             buf,
             separator: b',',
             state: IterState::Cell(0),
+            back_cell: false,
         }
     }
 
@@ -148,6 +200,7 @@ impl<'a> Csv<'a> {
             buf,
             separator,
             state: IterState::Cell(0),
+            back_cell: false,
         }
     }
 
@@ -200,6 +253,417 @@ impl<'a> Csv<'a> {
         }
     }
 
+    /// Create a wrapper iterator that yields items along with their byte position range.
+    ///
+    /// The range of a cell covers its raw content including the surrounding quotes, and the range
+    /// of a line break covers either `\n` or `\r\n`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, Csv, CsvIterItem};
+    ///
+    /// let mut items = Csv::new(b"a,\"b\"\r\n").into_items_with_range();
+    /// assert_eq!(items.next(), Some((CsvIterItem::Cell(Cell { buf: b"a" }), 0..1)));
+    /// assert_eq!(items.next(), Some((CsvIterItem::Cell(Cell { buf: b"\"b\"" }), 2..5)));
+    /// assert_eq!(items.next(), Some((CsvIterItem::LineEnd, 5..7)));
+    /// assert_eq!(items.next(), None);
+    /// ```
+    pub fn into_items_with_range(self) -> CsvItemWithRangeIter<'a> {
+        CsvItemWithRangeIter { csv: self }
+    }
+
+    /// Create a wrapper iterator that buffers the cells per row, along with byte position range
+    /// of each cell and the row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _: Option<()> = (|| {
+    /// use lazycsv::Csv;
+    ///
+    /// let mut rows = Csv::new(b"a,b\n1,\"2\"\n").into_rows_with_cell_range();
+    /// let ([one, two], range) = rows.nth(1)?.ok()?;
+    /// assert_eq!((one.cell.buf, one.range), (&b"1"[..], 4..5));
+    /// assert_eq!((two.cell.buf, two.range), (&b"\"2\""[..], 6..9));
+    /// assert_eq!(range, 4..10);
+    /// # None
+    /// # })();
+    /// ```
+    pub fn into_rows_with_cell_range<const COLS: usize>(self) -> CsvRowWithCellRangeIter<'a, COLS> {
+        CsvRowWithCellRangeIter { csv: self }
+    }
+
+    /// Create a wrapper iterator that only yields rows containing `needle`, along with byte position range.
+    ///
+    /// The buffer is searched for `needle` with a vectorized substring search first, and only the
+    /// rows containing a match are split into cells. This is much faster than comparing the cells
+    /// of every row when matching rows are rare.
+    ///
+    /// To tell whether a match is inside a quoted cell, the quotes between the previous row and
+    /// the match are counted with a vectorized search as well. This is nearly free for unquoted
+    /// input, but costs a pass over quote-heavy input on top of the substring search.
+    ///
+    /// Note that `needle` is matched against the raw bytes of the row, so it can match any cell
+    /// (or span multiple cells) and quoted cells are not dequoted beforehand. Compare the cells of
+    /// the yielded rows if an exact match on a specific column is needed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "alloc")]
+    /// # {
+    /// use lazycsv::Csv;
+    ///
+    /// let csv = Csv::new(b"isrc,title\nUSRC17607839,a\nGBAYE0601498,b\n");
+    /// for row in csv.skip_rows(1).into_rows_containing(b"GBAYE0601498") {
+    ///     let ([isrc, title], range) = row?;
+    ///     assert_eq!(isrc.try_as_str()?, "GBAYE0601498");
+    ///     assert_eq!(title.try_as_str()?, "b");
+    ///     assert_eq!(range, 26..41);
+    /// }
+    /// # }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn into_rows_containing<'n, const COLS: usize>(
+        self,
+        needle: &'n [u8],
+    ) -> CsvRowContainingIter<'a, 'n, COLS> {
+        CsvRowContainingIter {
+            csv: self,
+            finder: memmem::Finder::new(needle),
+        }
+    }
+
+    /// Create a wrapper iterator that yields the cell at column `index` of each row.
+    ///
+    /// Only the cells up to `index` are recognized. The rest of each row is skipped by looking for
+    /// the next line break outside quotes, which is faster than parsing the whole row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, Csv, RowIterError};
+    ///
+    /// let mut titles = Csv::new(b"a,\"b,c\",d\ne,f\ng\n").column(1);
+    /// assert_eq!(titles.next(), Some(Ok(Cell { buf: b"\"b,c\"" })));
+    /// assert_eq!(titles.next(), Some(Ok(Cell { buf: b"f" })));
+    /// assert_eq!(
+    ///     titles.next(),
+    ///     Some(Err(RowIterError::ColumnCountSmallerThanExpected {
+    ///         expected: 2,
+    ///         actual: 1,
+    ///     })),
+    /// );
+    /// assert_eq!(titles.next(), None);
+    /// ```
+    pub fn column(self, index: usize) -> CsvColumnIter<'a> {
+        CsvColumnIter { csv: self, index }
+    }
+
+    /// Reads the header row, and creates a wrapper iterator that yields the cell of each following
+    /// row at the column named `name`.
+    ///
+    /// Header names are dequoted before being compared. Returns `None` if no column is named
+    /// `name`. See [`Csv::column()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, Csv};
+    ///
+    /// let csv = Csv::new(b"isrc,\"title\"\nUSRC17607839,Song A\n");
+    /// let titles: Vec<_> = csv.column_by_name(b"title").unwrap().collect();
+    /// assert_eq!(titles, [Ok(Cell { buf: b"Song A" })]);
+    /// assert!(csv.column_by_name(b"units").is_none());
+    /// ```
+    pub fn column_by_name(mut self, name: &[u8]) -> Option<CsvColumnIter<'a>> {
+        let mut index = None;
+        let mut column = 0;
+        while let Some(CsvIterItem::Cell(cell)) = self.next() {
+            if index.is_none() && cell.eq_unquoted(name) {
+                index = Some(column);
+            }
+            column += 1;
+        }
+        index.map(|index| self.column(index))
+    }
+
+    /// Collects the cells at column `index` of each row into a vector.
+    ///
+    /// See [`Csv::column()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a row has `index` or fewer cells.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, Csv};
+    ///
+    /// let cells = Csv::new(b"a,1\nb,2\n").collect_column(1)?;
+    /// assert_eq!(cells, [Cell { buf: b"1" }, Cell { buf: b"2" }]);
+    /// # Ok::<(), lazycsv::RowIterError>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn collect_column(self, index: usize) -> Result<Vec<Cell<'a>>, RowIterError> {
+        self.column(index).collect()
+    }
+
+    /// Create a wrapper iterator that skips malformed rows instead of failing on them, along with
+    /// byte position range.
+    ///
+    /// Rows with a wrong number of columns or broken quoting are recorded in a report, and at most
+    /// `max_errors` of them are tolerated. See the [`lenient`] module for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Csv;
+    ///
+    /// let mut rows = Csv::new(b"1,2\n3\n4,5\n").into_rows_lenient(10);
+    /// for row in rows.by_ref() {
+    ///     let ([a, b], _range) = row?;
+    ///     println!("{}, {}", a.try_as_str()?, b.try_as_str()?);
+    /// }
+    /// assert_eq!(rows.skipped()[0].range, 4..6);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_rows_lenient<const COLS: usize>(
+        self,
+        max_errors: usize,
+    ) -> lenient::LenientRowIter<'a, COLS> {
+        lenient::LenientRowIter::new(self, max_errors)
+    }
+
+    /// Create a wrapper iterator that parses each row into `T`, e.g. a tuple of cell types.
+    ///
+    /// See the [`typed`] module for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Csv;
+    ///
+    /// let mut rows = Csv::new(b"1,a,2.5\n2,b,x\n").into_typed_rows::<(u32, &str, f64)>();
+    /// assert_eq!(rows.next(), Some(Ok((1, "a", 2.5))));
+    /// let err = rows.next().unwrap().unwrap_err();
+    /// assert_eq!(err.to_string(), "column 2 at byte 12: invalid float literal");
+    /// ```
+    pub fn into_typed_rows<T: typed::FromRow<'a>>(self) -> typed::CsvTypedRowIter<'a, T> {
+        typed::CsvTypedRowIter::new(self)
+    }
+
+    /// Create a wrapper iterator that reads the header row, and parses each following row into
+    /// `T` by mapping its fields to columns with the same header names.
+    ///
+    /// Header names are dequoted before being compared. If `T` has no [`typed::FromRow::HEADERS`],
+    /// e.g. a tuple, the header row is skipped and the rows are parsed by position. See the
+    /// [`typed`] module for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`typed::TypedRowError::MissingColumn`] if a column of a non-optional field is
+    /// missing from the header, or [`typed::TypedRowError::Cell`] if a header name is not valid
+    /// UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{
+    ///     Csv,
+    ///     typed::{FromRow, HeaderField, RowCells, TypedRowError},
+    /// };
+    ///
+    /// struct Song<'a> {
+    ///     isrc: &'a str,
+    ///     units: u64,
+    /// }
+    ///
+    /// impl<'a> FromRow<'a> for Song<'a> {
+    ///     const COLS: usize = 2;
+    ///     const HEADERS: Option<&'static [HeaderField]> = Some(&[
+    ///         HeaderField { name: "ISRC", optional: false },
+    ///         HeaderField { name: "Units", optional: false },
+    ///     ]);
+    ///
+    ///     fn from_row(cells: &mut RowCells<'_, 'a>) -> Result<Self, TypedRowError> {
+    ///         Ok(Song {
+    ///             isrc: cells.next_cell()?,
+    ///             units: cells.next_cell()?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let csv = Csv::new(b"Units,Title,ISRC\n3,Song A,USRC17607839\n");
+    /// for song in csv.into_typed_rows_by_header::<Song>()? {
+    ///     let song = song?;
+    ///     assert_eq!((song.isrc, song.units), ("USRC17607839", 3));
+    /// }
+    /// # Ok::<(), TypedRowError>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_typed_rows_by_header<T: typed::FromRow<'a>>(
+        self,
+    ) -> Result<typed::CsvTypedRowIter<'a, T>, typed::TypedRowError> {
+        typed::CsvTypedRowIter::with_header(self)
+    }
+
+    /// Create a wrapper iterator that yields each row as a [`::csv::ByteRecord`], for interop with
+    /// the `csv` crate.
+    ///
+    /// See the [`csv`] module for details.
+    #[cfg(feature = "csv")]
+    pub fn into_byte_records(self) -> csv::ByteRecordIter<'a> {
+        csv::ByteRecordIter::new(self)
+    }
+
+    /// Create a wrapper iterator that yields each row as a [`::csv::StringRecord`], for interop
+    /// with the `csv` crate.
+    ///
+    /// See the [`csv`] module for details.
+    #[cfg(feature = "csv")]
+    pub fn into_string_records(self) -> csv::StringRecordIter<'a> {
+        csv::StringRecordIter::new(self)
+    }
+
+    /// Create a wrapper iterator that fails once the input exceeds the given resource limits.
+    ///
+    /// This is useful for parsing untrusted input. See the [`limit`] module for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Csv, limit::Limits};
+    ///
+    /// let limits = Limits {
+    ///     max_columns: 2,
+    ///     ..Default::default()
+    /// };
+    /// let items = Csv::new(b"a,b\n1,2,3\n").into_limited(limits);
+    /// assert!(items.collect::<Result<Vec<_>, _>>().is_err());
+    /// ```
+    pub fn into_limited(self, limits: limit::Limits) -> limit::CsvLimitedIter<'a> {
+        limit::CsvLimitedIter::new(self, limits)
+    }
+
+    /// Counts the line breaks terminating the remaining rows, by their kind.
+    ///
+    /// Line breaks inside quoted cells are not counted, as they're part of the cell contents. This
+    /// only looks for line breaks and quotes instead of trying to recognize cells, so it's cheaper
+    /// than iterating over the items.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Csv, LineEnding};
+    ///
+    /// let stats = Csv::new(b"a,b\r\n\"1\n2\",3\r\n4,5\n").line_ending_stats();
+    /// assert_eq!((stats.lf, stats.crlf), (1, 2));
+    /// assert_eq!(stats.dominant(), Some(LineEnding::Crlf));
+    /// ```
+    pub fn line_ending_stats(&self) -> LineEndingStats {
+        let mut stats = LineEndingStats::default();
+        let mut cursor = match self.state {
+            IterState::Cell(pos) | IterState::LineEnd(pos, _) => pos,
+            IterState::Done => return stats,
+        };
+        let mut in_quoted_state = false;
+        while let Some(index_relative) = memchr2(b'\n', b'"', &self.buf[cursor..]) {
+            let index = index_relative + cursor;
+            cursor = index + 1;
+            if self.buf[index] == b'"' {
+                in_quoted_state = !in_quoted_state;
+            } else if in_quoted_state {
+                continue;
+            } else if index > 0 && self.buf[index - 1] == b'\r' {
+                stats.crlf += 1;
+            } else {
+                stats.lf += 1;
+            }
+        }
+        stats
+    }
+
+    /// Counts the remaining records, including the current one if its line break hasn't been read.
+    ///
+    /// Only line breaks outside quoted cells are counted, as in [`Csv::line_ending_stats()`], so
+    /// this is much faster than iterating over the items. A trailing record without a line break
+    /// is counted as well. If a quote is never closed, the rest of the input is counted as a
+    /// single record, whereas iterating stops at the unterminated cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Csv;
+    ///
+    /// let csv = Csv::new(b"a,b\n\"1\n2\",3\n4,5");
+    /// assert_eq!(csv.count_records(), 3);
+    /// assert_eq!(csv.skip_rows(1).count_records(), 2);
+    /// ```
+    pub fn count_records(&self) -> usize {
+        match self.state {
+            IterState::Cell(pos) | IterState::LineEnd(pos, _) => self.count_unquoted(pos, false).0,
+            IterState::Done => 0,
+        }
+    }
+
+    /// Counts the remaining cells, in the same way as [`Csv::count_records()`].
+    ///
+    /// Comparing this to the number of records is a cheap way to check that every record has the
+    /// same number of columns, before parsing them with a fixed column count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Csv;
+    ///
+    /// let csv = Csv::new(b"a,b\n\"1,2\",3\n4,5\n");
+    /// assert_eq!(csv.count_cells(), 6);
+    /// assert_eq!(csv.count_cells(), csv.count_records() * 2);
+    /// ```
+    pub fn count_cells(&self) -> usize {
+        let start = match self.state {
+            IterState::Cell(pos) => pos,
+            IterState::LineEnd(pos, is_crlf) => pos + 1 + (is_crlf as usize),
+            IterState::Done => return 0,
+        };
+        let (records, separators) = self.count_unquoted(start, true);
+        records + separators
+    }
+
+    /// Counts the records and, if `count_separators` is set, the separators outside quoted cells
+    /// from `start`.
+    fn count_unquoted(&self, start: usize, count_separators: bool) -> (usize, usize) {
+        let buf = &self.buf[start..];
+        let mut records = 0;
+        let mut separators = 0;
+        let mut record_start = 0;
+        let mut in_quoted_state = false;
+        let mut visit = |index: usize| match buf[index] {
+            b'"' => in_quoted_state = !in_quoted_state,
+            _ if in_quoted_state => {}
+            b'\n' => {
+                records += 1;
+                record_start = index + 1;
+            }
+            _ => separators += 1,
+        };
+        if count_separators {
+            memchr3_iter(b'\n', b'"', self.separator, buf).for_each(&mut visit);
+        } else {
+            memchr2_iter(b'\n', b'"', buf).for_each(&mut visit);
+        }
+
+        // The last record may not end with a line break.
+        if record_start < buf.len() {
+            records += 1;
+        }
+        (records, separators)
+    }
+
     /// Skips the first `n` rows.
     ///
     /// Using this function is more efficient than calling [`Iterator::skip()`] on the row iterator made with [`Csv::into_rows()`],
@@ -238,6 +702,30 @@ impl<'a> Csv<'a> {
         self
     }
 
+    /// Moves to the start of the next row, only looking for the next line break outside quotes.
+    fn skip_record(&mut self) {
+        let mut cursor = match self.state {
+            IterState::Cell(pos) => pos,
+            IterState::LineEnd(pos, is_crlf) => {
+                self.state = IterState::Cell(pos + 1 + (is_crlf as usize));
+                return;
+            }
+            IterState::Done => return,
+        };
+        let mut in_quoted_state = false;
+        while let Some(index_relative) = memchr2(b'\n', b'"', &self.buf[cursor..]) {
+            let index = index_relative + cursor;
+            cursor = index + 1;
+            if self.buf[index] == b'"' {
+                in_quoted_state = !in_quoted_state;
+            } else if !in_quoted_state {
+                self.state = IterState::Cell(cursor);
+                return;
+            }
+        }
+        self.state = IterState::Done;
+    }
+
     /// Returns the current byte position of the parser within the input buffer.
     ///
     /// This indicates the starting position of the *next* item (cell or line break)
@@ -288,6 +776,52 @@ enum IterState {
     Done,
 }
 
+/// A kind of line break terminating rows.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum LineEnding {
+    /// `\n`, as on Unix.
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows and in RFC 4180.
+    Crlf,
+}
+
+impl LineEnding {
+    /// Returns the bytes of the line break.
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::Crlf => b"\r\n",
+        }
+    }
+}
+
+/// Counts of line breaks by their kind, returned by [`Csv::line_ending_stats()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct LineEndingStats {
+    /// The number of rows terminated by `\n` alone.
+    pub lf: usize,
+    /// The number of rows terminated by `\r\n`.
+    pub crlf: usize,
+}
+
+impl LineEndingStats {
+    /// Returns the more common line ending, preferring LF on a tie, or `None` if there are no
+    /// line breaks at all.
+    pub fn dominant(&self) -> Option<LineEnding> {
+        match (self.lf, self.crlf) {
+            (0, 0) => None,
+            (lf, crlf) if crlf > lf => Some(LineEnding::Crlf),
+            _ => Some(LineEnding::Lf),
+        }
+    }
+
+    /// Returns `true` if both kinds of line breaks are present.
+    pub fn is_mixed(&self) -> bool {
+        self.lf != 0 && self.crlf != 0
+    }
+}
+
 /// An item yielded by [`Csv`], indicates either a cell or a line break.
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum CsvIterItem<'a> {
@@ -332,8 +866,9 @@ impl<'a> Iterator for Csv<'a> {
                     Some(CsvIterItem::Cell(Cell {
                         buf: &self.buf[start..],
                     }))
-                } else if self.buf.ends_with(&[self.separator]) {
-                    // Handle trailing empty cell when no trailing newline is present.
+                } else if self.buf.ends_with(&[self.separator]) || self.back_cell {
+                    // Handle trailing empty cell when no trailing newline is present, or when
+                    // the following separator has already been consumed by `next_back()`.
                     Some(CsvIterItem::Cell(Cell { buf: &[] }))
                 } else {
                     // Gracefully reached EOF with no more data
@@ -370,6 +905,105 @@ impl<'a> Iterator for Csv<'a> {
     }
 }
 
+/// Iterates over the CSV from the end of the buffer.
+///
+/// Items are yielded in the exact reverse order of [`Iterator::next()`], so a trailing line break
+/// is yielded first as [`CsvIterItem::LineEnd`]. Both ends can be consumed alternately, and the
+/// iteration stops once they meet.
+///
+/// Quoted cells are recognized by pairing quotes from the end, which matches the forward parser as
+/// long as every quote in the buffer is closed.
+///
+/// # Example
+///
+/// ```
+/// use lazycsv::{Cell, Csv, CsvIterItem};
+///
+/// let mut csv = Csv::new(b"a,b\n\"1,\"\"x\"\"\",2\n");
+/// assert_eq!(csv.next_back(), Some(CsvIterItem::LineEnd));
+/// assert_eq!(csv.next_back(), Some(CsvIterItem::Cell(Cell { buf: b"2" })));
+/// assert_eq!(
+///     csv.next_back(),
+///     Some(CsvIterItem::Cell(Cell { buf: b"\"1,\"\"x\"\"\"" })),
+/// );
+/// assert_eq!(csv.next(), Some(CsvIterItem::Cell(Cell { buf: b"a" })));
+/// ```
+impl DoubleEndedIterator for Csv<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let front = match self.state {
+            IterState::Cell(pos) | IterState::LineEnd(pos, _) => pos,
+            IterState::Done => return None,
+        };
+        let end = self.buf.len();
+
+        if !self.back_cell && end > front && self.buf[end - 1] == b'\n' {
+            let is_crlf = end >= 2 && self.buf[end - 2] == b'\r';
+            let new_end = end - 1 - (is_crlf as usize);
+            if new_end == front && matches!(self.state, IterState::LineEnd(..)) {
+                // The line break was pending in the forward direction, so both ends met here.
+                self.state = IterState::Done;
+            }
+            self.buf = &self.buf[..new_end];
+            self.back_cell = true;
+            return Some(CsvIterItem::LineEnd);
+        }
+
+        let mut cursor = end;
+        let (start, preceded_by) = loop {
+            let Some(index_relative) =
+                memrchr3(self.separator, b'\n', b'"', &self.buf[front..cursor])
+            else {
+                break (front, None);
+            };
+            let index = index_relative + front;
+
+            // SAFETY: `memrchr3` guarantees that `index_relative` is within the bounds of
+            // `self.buf[front..cursor]`, so `index` is within the bounds of `self.buf`.
+            let c = unsafe { *self.buf.get_unchecked(index) };
+            if c == b'"' {
+                // Jump to the opening quote. Escaped quotes are just empty quoted sections.
+                let Some(opening) = memrchr(b'"', &self.buf[front..index]) else {
+                    self.state = IterState::Done;
+                    return None;
+                };
+                cursor = opening + front;
+                continue;
+            }
+            break (index + 1, Some(c));
+        };
+
+        if start == end && !self.back_cell && preceded_by != Some(self.separator) {
+            // The remaining part is empty. It's still a cell if a separator precedes it.
+            let is_cell = preceded_by.is_none()
+                && matches!(self.state, IterState::Cell(_))
+                && front > 0
+                && self.buf[front - 1] == self.separator;
+            self.state = IterState::Done;
+            self.buf = &self.buf[..front];
+            return is_cell.then_some(CsvIterItem::Cell(Cell { buf: &[] }));
+        }
+
+        let cell = Cell {
+            buf: &self.buf[start..end],
+        };
+        match preceded_by {
+            None => {
+                self.state = IterState::Done;
+                self.buf = &self.buf[..start];
+            }
+            Some(b'\n') => {
+                self.buf = &self.buf[..start];
+                self.back_cell = false;
+            }
+            Some(_) => {
+                self.buf = &self.buf[..start - 1];
+                self.back_cell = true;
+            }
+        }
+        Some(CsvIterItem::Cell(cell))
+    }
+}
+
 /// An iterator that buffers and yields rows of cells.
 ///
 /// Can be created by calling [`Csv::into_rows()`].
@@ -413,16 +1047,32 @@ impl<'a, const COLS: usize> Iterator for CsvRowIter<'a, COLS> {
     type Item = Result<[Cell<'a>; COLS], RowIterError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.csv.next_row(|cell, _| cell)
+    }
+}
+
+impl<'a> Csv<'a> {
+    /// Buffers the cells of the next row, mapping each cell with its starting position.
+    fn next_row<T, const COLS: usize>(
+        &mut self,
+        mut f: impl FnMut(Cell<'a>, usize) -> T,
+    ) -> Option<Result<[T; COLS], RowIterError>> {
         let mut arr = [const { MaybeUninit::uninit() }; COLS];
         for i in 0..COLS {
-            match self.csv.next() {
+            let position = Csv::position(self);
+            match self.next() {
                 // If we reach EOF before reading any cells, there are no more rows available.
                 None if i == 0 => return None,
                 Some(CsvIterItem::Cell(cell)) => {
                     // SAFETY: we have to initialize the cell beforehand
-                    unsafe { arr.get_unchecked_mut(i).write(cell) };
+                    unsafe { arr.get_unchecked_mut(i).write(f(cell, position)) };
                 }
                 None | Some(CsvIterItem::LineEnd) => {
+                    // Drop the cells initialized so far.
+                    for mem in &mut arr[..i] {
+                        // SAFETY: the first `i` elements are initialized above.
+                        unsafe { mem.assume_init_drop() };
+                    }
                     return Some(Err(RowIterError::ColumnCountSmallerThanExpected {
                         expected: COLS,
                         actual: i,
@@ -434,8 +1084,9 @@ impl<'a, const COLS: usize> Iterator for CsvRowIter<'a, COLS> {
         // After reading COLS cells, the next item must be a line ending or EOF.
         // EOF in this context is treated as a valid input to gracefully handle
         // files without a trailing newline.
-        if let None | Some(CsvIterItem::LineEnd) = self.csv.next() {
-            Some(Ok(arr.map(|mem| unsafe { mem.assume_init() })))
+        let arr = arr.map(|mem| unsafe { mem.assume_init() });
+        if let None | Some(CsvIterItem::LineEnd) = self.next() {
+            Some(Ok(arr))
         } else {
             Some(Err(RowIterError::ColumnCountLargerThanExpected {
                 expected: COLS,
@@ -444,16 +1095,72 @@ impl<'a, const COLS: usize> Iterator for CsvRowIter<'a, COLS> {
     }
 }
 
-/// An iterator that buffers and yields rows of cells along with byte position range.
+/// Iterates over the rows from the end of the buffer.
 ///
-/// Can be created by calling [`Csv::into_rows_with_range()`].
+/// This is useful for reading trailer rows (e.g. totals) without parsing the whole buffer.
 ///
-/// ### `const` Parameters
+/// # Example
 ///
-/// - `COLS`: The number of columns in the CSV.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-pub struct CsvRowWithRangeIter<'a, const COLS: usize> {
-    row_iter: CsvRowIter<'a, COLS>,
+/// ```
+/// # let _: Option<()> = (|| {
+/// use lazycsv::Csv;
+///
+/// let mut rows = Csv::new(b"a,b\n1,2\ntotal,3\n").into_rows();
+/// let [label, total] = rows.next_back()?.ok()?;
+/// assert_eq!([label.buf, total.buf], [&b"total"[..], b"3"]);
+/// # None
+/// # })();
+/// ```
+impl<const COLS: usize> DoubleEndedIterator for CsvRowIter<'_, COLS> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut arr = [const { MaybeUninit::uninit() }; COLS];
+        let mut item = self.csv.next_back();
+        if let Some(CsvIterItem::LineEnd) = item {
+            item = self.csv.next_back();
+        }
+        for i in (0..COLS).rev() {
+            match item {
+                // If we reach the beginning before reading any cells, there are no more rows available.
+                None if i == COLS - 1 => return None,
+                Some(CsvIterItem::Cell(cell)) => {
+                    // SAFETY: we have to initialize the cell beforehand
+                    unsafe { arr.get_unchecked_mut(i).write(cell) };
+                }
+                None | Some(CsvIterItem::LineEnd) => {
+                    return Some(Err(RowIterError::ColumnCountSmallerThanExpected {
+                        expected: COLS,
+                        actual: COLS - 1 - i,
+                    }));
+                }
+            }
+            if i != 0 {
+                item = self.csv.next_back();
+            }
+        }
+
+        // The row must start right after a line break or at the beginning of the buffer. The line
+        // break is left unconsumed, as it terminates the previous row.
+        let mut csv = self.csv;
+        if let None | Some(CsvIterItem::LineEnd) = csv.next_back() {
+            Some(Ok(arr.map(|mem| unsafe { mem.assume_init() })))
+        } else {
+            Some(Err(RowIterError::ColumnCountLargerThanExpected {
+                expected: COLS,
+            }))
+        }
+    }
+}
+
+/// An iterator that buffers and yields rows of cells along with byte position range.
+///
+/// Can be created by calling [`Csv::into_rows_with_range()`].
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the CSV.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CsvRowWithRangeIter<'a, const COLS: usize> {
+    row_iter: CsvRowIter<'a, COLS>,
 }
 
 impl<const COLS: usize> CsvRowWithRangeIter<'_, COLS> {
@@ -495,6 +1202,210 @@ impl<'a, const COLS: usize> Iterator for CsvRowWithRangeIter<'a, COLS> {
     }
 }
 
+impl<const COLS: usize> DoubleEndedIterator for CsvRowWithRangeIter<'_, COLS> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let end = self.row_iter.csv.buf.len();
+        let next = self.row_iter.next_back();
+        let start = self.row_iter.csv.buf.len();
+        next.map(|res| res.map(|arr| (arr, start..end)))
+    }
+}
+
+/// An iterator that yields items along with their byte position range.
+///
+/// Can be created by calling [`Csv::into_items_with_range()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CsvItemWithRangeIter<'a> {
+    csv: Csv<'a>,
+}
+
+impl<'a> CsvItemWithRangeIter<'a> {
+    /// Returns the underlying parser, which continues from the current position.
+    pub fn into_inner(self) -> Csv<'a> {
+        self.csv
+    }
+}
+
+impl<'a> Iterator for CsvItemWithRangeIter<'a> {
+    type Item = (CsvIterItem<'a>, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.csv.position();
+        let item = self.csv.next()?;
+        let end = match &item {
+            CsvIterItem::Cell(cell) => start + cell.buf.len(),
+            CsvIterItem::LineEnd => self.csv.position(),
+        };
+        Some((item, start..end))
+    }
+}
+
+/// An iterator that buffers and yields rows of cells along with byte position range of each cell
+/// and the row.
+///
+/// Can be created by calling [`Csv::into_rows_with_cell_range()`].
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the CSV.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CsvRowWithCellRangeIter<'a, const COLS: usize> {
+    csv: Csv<'a>,
+}
+
+impl<const COLS: usize> CsvRowWithCellRangeIter<'_, COLS> {
+    /// Skips the first `n` rows.
+    ///
+    /// Using this function is more efficient than calling [`Iterator::skip()`],
+    /// as it only looks for newline characters instead of trying to recognize cells.
+    pub fn skip(self, n: usize) -> Self {
+        Self {
+            csv: self.csv.skip_rows(n),
+        }
+    }
+}
+
+impl<'a, const COLS: usize> Iterator for CsvRowWithCellRangeIter<'a, COLS> {
+    type Item = Result<([CellWithRange<'a>; COLS], Range<usize>), RowIterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.csv.position();
+        let next = self.csv.next_row(|cell, position| CellWithRange {
+            range: position..position + cell.buf.len(),
+            cell,
+        });
+        let end = self.csv.position();
+        next.map(|res| res.map(|arr| (arr, start..end)))
+    }
+}
+
+/// An iterator that yields rows containing a specific byte sequence, along with byte position range.
+///
+/// Can be created by calling [`Csv::into_rows_containing()`].
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the CSV.
+#[derive(Clone, Debug)]
+pub struct CsvRowContainingIter<'a, 'n, const COLS: usize> {
+    csv: Csv<'a>,
+    finder: memmem::Finder<'n>,
+}
+
+impl<'a, const COLS: usize> Iterator for CsvRowContainingIter<'a, '_, COLS> {
+    type Item = Result<([Cell<'a>; COLS], Range<usize>), RowIterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buf = self.csv.buf;
+        let pos = match self.csv.state {
+            IterState::Cell(pos) => pos,
+            IterState::LineEnd(pos, is_crlf) => pos + 1 + (is_crlf as usize),
+            IterState::Done => return None,
+        };
+        // Nothing is left after the last row, not even when it ends with a separator. An empty
+        // needle would match here otherwise.
+        if pos >= buf.len() {
+            self.csv.state = IterState::Done;
+            return None;
+        }
+        let Some(hit) = self.finder.find(&buf[pos..]) else {
+            self.csv.state = IterState::Done;
+            return None;
+        };
+        let hit = hit + pos;
+
+        // Whether the match is inside quotes only depends on the number of quotes before it. This
+        // doesn't stop at line breaks, so rows without a match are skipped at full speed.
+        let quoted_at_hit = memchr_iter(b'"', &buf[pos..hit]).count() % 2 == 1;
+
+        // Find the boundaries of the row containing the match, only looking at line breaks and
+        // quotes around it instead of recognizing cells.
+        let mut start = pos;
+        let mut cursor = hit;
+        let mut in_quoted_state = quoted_at_hit;
+        while let Some(index_relative) = memrchr2(b'\n', b'"', &buf[pos..cursor]) {
+            let index = index_relative + pos;
+            cursor = index;
+            if buf[index] == b'"' {
+                in_quoted_state = !in_quoted_state;
+            } else if !in_quoted_state {
+                start = index + 1;
+                break;
+            }
+        }
+        let mut cursor = hit;
+        let mut in_quoted_state = quoted_at_hit;
+        let end = loop {
+            let Some(index_relative) = memchr2(b'\n', b'"', &buf[cursor..]) else {
+                break buf.len();
+            };
+            let index = index_relative + cursor;
+            cursor = index + 1;
+            if buf[index] == b'"' {
+                in_quoted_state = !in_quoted_state;
+            } else if !in_quoted_state {
+                break cursor;
+            }
+        };
+
+        self.csv.state = IterState::Cell(end);
+        let row = Csv {
+            buf: &buf[..end],
+            separator: self.csv.separator,
+            state: IterState::Cell(start),
+            back_cell: false,
+        }
+        .into_rows()
+        .next()?;
+        Some(row.map(|arr| (arr, start..end)))
+    }
+}
+
+/// An iterator that yields the cell at a given column of each row.
+///
+/// Can be created by calling [`Csv::column()`] or [`Csv::column_by_name()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CsvColumnIter<'a> {
+    csv: Csv<'a>,
+    index: usize,
+}
+
+impl CsvColumnIter<'_> {
+    /// Skips the first `n` rows.
+    ///
+    /// Using this function is more efficient than calling [`Iterator::skip()`],
+    /// as it only looks for newline characters instead of trying to recognize cells.
+    pub fn skip(mut self, n: usize) -> Self {
+        self.csv = self.csv.skip_rows(n);
+        self
+    }
+}
+
+impl<'a> Iterator for CsvColumnIter<'a> {
+    type Item = Result<Cell<'a>, RowIterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut column = 0;
+        loop {
+            match self.csv.next() {
+                Some(CsvIterItem::Cell(cell)) if column == self.index => {
+                    self.csv.skip_record();
+                    return Some(Ok(cell));
+                }
+                Some(CsvIterItem::Cell(_)) => column += 1,
+                // If we reach EOF before reading any cells, there are no more rows available.
+                None if column == 0 => return None,
+                Some(CsvIterItem::LineEnd) | None => {
+                    return Some(Err(RowIterError::ColumnCountSmallerThanExpected {
+                        expected: self.index + 1,
+                        actual: column,
+                    }));
+                }
+            }
+        }
+    }
+}
+
 /// Errors returned by [`CsvRowIter`].
 #[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum RowIterError {
@@ -515,6 +1426,22 @@ pub enum RowIterError {
     },
 }
 
+/// Errors returned by [`Cell::validate_quoting()`].
+#[derive(Error, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum QuoteError {
+    /// A quote appears in a cell that doesn't start with a quote.
+    #[error("quote inside an unquoted cell")]
+    QuoteInUnquotedCell,
+
+    /// A quoted cell doesn't end right after its closing quote.
+    #[error("unexpected data after the closing quote")]
+    DataAfterClosingQuote,
+
+    /// A quote inside a quoted cell is not escaped by doubling it.
+    #[error("unescaped quote inside a quoted cell")]
+    UnescapedQuote,
+}
+
 /// A cell in a CSV row.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Cell<'a> {
@@ -522,6 +1449,306 @@ pub struct Cell<'a> {
     pub buf: &'a [u8],
 }
 
+impl<'a> Cell<'a> {
+    /// Checks that quotes only appear around the whole cell, and are escaped inside of it.
+    ///
+    /// The parser itself accepts any quote, so this can be used to find cells that other CSV
+    /// readers would interpret differently.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, QuoteError};
+    ///
+    /// assert_eq!(Cell { buf: b"\"a\"\"b\"" }.validate_quoting(), Ok(()));
+    /// assert_eq!(
+    ///     Cell { buf: b"\"a\"b" }.validate_quoting(),
+    ///     Err(QuoteError::DataAfterClosingQuote),
+    /// );
+    /// ```
+    pub fn validate_quoting(&self) -> Result<(), QuoteError> {
+        let Some(rest) = self.buf.strip_prefix(b"\"") else {
+            return match memchr(b'"', self.buf) {
+                Some(_) => Err(QuoteError::QuoteInUnquotedCell),
+                None => Ok(()),
+            };
+        };
+        let Some(content) = rest.strip_suffix(b"\"") else {
+            return Err(QuoteError::DataAfterClosingQuote);
+        };
+
+        let mut cursor = 0;
+        while let Some(index_relative) = memchr(b'"', &content[cursor..]) {
+            let index = index_relative + cursor;
+            if content.get(index + 1) != Some(&b'"') {
+                return Err(QuoteError::UnescapedQuote);
+            }
+            cursor = index + 2;
+        }
+        Ok(())
+    }
+
+    /// Returns the dequoted content of the cell in chunks, without allocating.
+    ///
+    /// Concatenating the chunks gives the same bytes as [`Cell::try_as_str()`], even for malformed
+    /// cells: a cell is only dequoted if it both starts and ends with a quote, so an unterminated
+    /// cell like `"ab` is kept as-is. A new chunk starts after each escaped quote, so that the
+    /// quote escaping it can be left out. Empty chunks are never yielded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// let cell = Cell { buf: b"\"Hi, \"\"Quote\"\"\"" };
+    /// let chunks: Vec<_> = cell.unquoted_chunks().collect();
+    /// assert_eq!(chunks, [&b"Hi, \""[..], b"Quote\""]);
+    ///
+    /// let cell = Cell { buf: b"\"ab" };
+    /// assert_eq!(cell.unquoted_chunks().collect::<Vec<_>>(), [b"\"ab"]);
+    /// ```
+    pub fn unquoted_chunks(&self) -> UnquotedChunks<'a> {
+        let (rest, quoted) = match self.buf {
+            [b'"', content @ .., b'"'] => (content, true),
+            buf => (buf, false),
+        };
+        UnquotedChunks { rest, quoted }
+    }
+
+    /// Returns `true` if the dequoted cell is equal to `other`, without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// assert!(Cell { buf: b"\"a \"\"b\"\"\"" }.eq_unquoted(b"a \"b\""));
+    /// assert!(Cell { buf: b"a" }.eq_unquoted(b"a"));
+    /// assert!(!Cell { buf: b"\"a\"" }.eq_unquoted(b"\"a\""));
+    /// ```
+    pub fn eq_unquoted(&self, other: &[u8]) -> bool {
+        self.eq_unquoted_by(other, |chunk, other| chunk == other)
+    }
+
+    /// Returns `true` if the dequoted cell is equal to `other`, ignoring the case of ASCII
+    /// letters, without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// assert!(Cell { buf: b"\"Title\"" }.eq_unquoted_ignore_ascii_case(b"TITLE"));
+    /// assert!(!Cell { buf: b"Title" }.eq_unquoted_ignore_ascii_case(b"Titles"));
+    /// ```
+    pub fn eq_unquoted_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        self.eq_unquoted_by(other, <[u8]>::eq_ignore_ascii_case)
+    }
+
+    fn eq_unquoted_by(&self, mut other: &[u8], eq: impl Fn(&[u8], &[u8]) -> bool) -> bool {
+        for chunk in self.unquoted_chunks() {
+            match other.split_at_checked(chunk.len()) {
+                Some((head, tail)) if eq(chunk, head) => other = tail,
+                _ => return false,
+            }
+        }
+        other.is_empty()
+    }
+
+    /// Returns `true` if the dequoted cell starts with `prefix`, without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// assert!(Cell { buf: b"\"\"\"a\"\", b\"" }.starts_with_unquoted(b"\"a\","));
+    /// assert!(!Cell { buf: b"\"ab\"" }.starts_with_unquoted(b"\"a"));
+    /// ```
+    pub fn starts_with_unquoted(&self, mut prefix: &[u8]) -> bool {
+        for chunk in self.unquoted_chunks() {
+            if prefix.len() <= chunk.len() {
+                return chunk.starts_with(prefix);
+            }
+            match prefix.strip_prefix(chunk) {
+                Some(rest) => prefix = rest,
+                None => return false,
+            }
+        }
+        prefix.is_empty()
+    }
+
+    /// Feeds the dequoted cell into `state`, without allocating.
+    ///
+    /// Cells with equal dequoted content, e.g. `a` and `"a"`, always hash the same, regardless of
+    /// how their quotes are escaped. This makes it consistent with [`Cell::eq_unquoted()`], unlike
+    /// the [`Hash`] implementation of [`Cell`], which hashes the raw bytes. The content is prefixed
+    /// with its length, so hashing several cells in a row can't confuse where one cell ends.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::hash::{DefaultHasher, Hasher};
+    ///
+    /// use lazycsv::Cell;
+    ///
+    /// let hash = |cell: Cell| {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     cell.hash_unquoted(&mut hasher);
+    ///     hasher.finish()
+    /// };
+    /// assert_eq!(hash(Cell { buf: b"a\"b" }), hash(Cell { buf: b"\"a\"\"b\"" }));
+    /// assert_ne!(hash(Cell { buf: b"a" }), hash(Cell { buf: b"b" }));
+    /// ```
+    pub fn hash_unquoted<H: Hasher>(&self, state: &mut H) {
+        // Prefix the content with its length, so that hashing consecutive cells is prefix-free.
+        // Cells may contain any byte, so a terminator like the `0xff` of `str` wouldn't do.
+        let dequoted_len = self.unquoted_chunks().map(<[u8]>::len).sum();
+        state.write_usize(dequoted_len);
+
+        // Hashers may depend on how the input is split into writes, so the content is buffered
+        // into fixed-size writes that don't depend on where escaped quotes are.
+        let mut buf = [0; 64];
+        let mut len = 0;
+        for mut chunk in self.unquoted_chunks() {
+            while !chunk.is_empty() {
+                let n = chunk.len().min(buf.len() - len);
+                buf[len..len + n].copy_from_slice(&chunk[..n]);
+                (len, chunk) = (len + n, &chunk[n..]);
+                if len == buf.len() {
+                    state.write(&buf);
+                    len = 0;
+                }
+            }
+        }
+        state.write(&buf[..len]);
+    }
+
+    /// Returns `true` if the cell starts with a quote.
+    pub fn is_quoted(&self) -> bool {
+        self.buf.first() == Some(&b'"')
+    }
+
+    /// Returns `true` if the cell is empty and unquoted, i.e. null in [`NullSet::EMPTY`].
+    ///
+    /// A quoted empty cell (`""`) is an empty string rather than null. Use
+    /// [`Cell::is_null_in()`] to recognize other representations of null.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// assert!(Cell { buf: b"" }.is_null());
+    /// assert!(!Cell { buf: b"\"\"" }.is_null());
+    /// ```
+    pub fn is_null(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Returns `true` if the cell is unquoted and matches one of the values in `nulls`.
+    ///
+    /// Quoted cells are never null, so that e.g. `"NULL"` can still represent the string `NULL`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, NullSet};
+    ///
+    /// assert!(Cell { buf: b"N/A" }.is_null_in(&NullSet::COMMON));
+    /// assert!(!Cell { buf: b"\"N/A\"" }.is_null_in(&NullSet::COMMON));
+    /// assert!(!Cell { buf: b"N/A" }.is_null_in(&NullSet::EMPTY));
+    /// ```
+    pub fn is_null_in(&self, nulls: &NullSet<'_>) -> bool {
+        !self.is_quoted()
+            && nulls.values.iter().any(|value| {
+                if nulls.ignore_ascii_case {
+                    value.eq_ignore_ascii_case(self.buf)
+                } else {
+                    *value == self.buf
+                }
+            })
+    }
+}
+
+/// A set of unquoted cell contents representing null, used by [`Cell::is_null_in()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NullSet<'n> {
+    /// The contents representing null. Include `b""` to treat empty unquoted cells as null.
+    pub values: &'n [&'n [u8]],
+    /// Whether to compare the contents case-insensitively for ASCII letters.
+    pub ignore_ascii_case: bool,
+}
+
+impl NullSet<'static> {
+    /// Only empty unquoted cells are null.
+    pub const EMPTY: Self = NullSet {
+        values: &[b""],
+        ignore_ascii_case: false,
+    };
+
+    /// Empty unquoted cells, `NULL`, `\N` and `N/A` are null.
+    pub const COMMON: Self = NullSet {
+        values: &[b"", b"NULL", b"\\N", b"N/A"],
+        ignore_ascii_case: false,
+    };
+}
+
+impl Default for NullSet<'static> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// A cell along with its byte position range within the buffer.
+///
+/// Yielded by [`CsvRowWithCellRangeIter`]. The range covers the raw content of the cell, including
+/// the surrounding quotes if the cell is quoted.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CellWithRange<'a> {
+    /// The cell.
+    pub cell: Cell<'a>,
+    /// The byte position range of the cell.
+    pub range: Range<usize>,
+}
+
+/// An iterator over the dequoted content of a cell in chunks.
+///
+/// Can be created by calling [`Cell::unquoted_chunks()`].
+#[derive(Clone, Debug)]
+pub struct UnquotedChunks<'a> {
+    rest: &'a [u8],
+    quoted: bool,
+}
+
+impl<'a> Iterator for UnquotedChunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let index = match self.quoted {
+            true => memchr(b'"', self.rest),
+            false => None,
+        };
+        let Some(index) = index else {
+            return Some(core::mem::take(&mut self.rest));
+        };
+        // Keep the escaped quote, and skip the quote escaping it. A lone quote is kept as-is, like
+        // `Cell::try_as_str()` does.
+        let chunk = &self.rest[..=index];
+        let skip = match self.rest.get(index + 1) {
+            Some(b'"') => 2,
+            _ => 1,
+        };
+        self.rest = &self.rest[index + skip..];
+        Some(chunk)
+    }
+}
+
+impl FusedIterator for UnquotedChunks<'_> {}
+
 #[cfg(feature = "alloc")]
 impl<'a> Cell<'a> {
     /// Converts the cell to a string.
@@ -537,7 +1764,8 @@ impl<'a> Cell<'a> {
     ///
     /// Therefore, when using lazycsv, it is recommended—whenever possible—to avoid dequoting and
     /// instead access the underlying buffer directly, or limit dequoting to only a small subset of
-    /// cells.
+    /// cells. When many cells do need dequoting, [`dequote::DequoteBuf`] avoids allocating a new
+    /// string for each of them.
     ///
     /// If that’s not feasible and extensive dequoting is necessary, consider using an eager parser
     /// like [BurntSushi/rust-csv], which performs dequoting during parsing and avoids this
@@ -545,13 +1773,62 @@ impl<'a> Cell<'a> {
     ///
     /// [BurntSushi/rust-csv]: https://github.com/BurntSushi/rust-csv
     pub fn try_as_str(&self) -> Result<Cow<'a, str>, core::str::Utf8Error> {
-        core::str::from_utf8(self.buf).map(|s| {
-            // SAFETY: since `s.as_bytes()` is guaranteed to be valid UTF-8, it's also guaranteed that the first character is '"' if the first byte is b'"' due to UTF-8 representing ASCII characters as-is.
-            if !s.is_empty() && unsafe { *s.as_bytes().get_unchecked(0) } == b'"' {
-                Cow::Owned(s[1..(s.len() - 1)].replace("\"\"", "\""))
-            } else {
-                Cow::Borrowed(s)
-            }
+        core::str::from_utf8(self.buf).map(|s| match s.as_bytes() {
+            // Only cells with both quotes are dequoted, so a lone or unterminated quote (which
+            // can't come from a well-formed CSV, but can from e.g. a fixed-width file) is kept.
+            // Quotes are ASCII, so slicing them off leaves valid character boundaries.
+            [b'"', .., b'"'] => Cow::Owned(s[1..(s.len() - 1)].replace("\"\"", "\"")),
+            _ => Cow::Borrowed(s),
         })
     }
+
+    /// Parses the dequoted cell as `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::Cell;
+    ///
+    /// assert_eq!(Cell { buf: b"\"42\"" }.parse::<u32>()?, 42);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse<T: FromStr>(&self) -> Result<T, CellParseError<T::Err>> {
+        self.try_as_str()?.parse().map_err(CellParseError::Parse)
+    }
+
+    /// Parses the dequoted cell as `T`, or returns `None` if the cell is null in `nulls`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Cell, NullSet};
+    ///
+    /// assert_eq!(Cell { buf: b"\\N" }.parse_nullable::<u32>(&NullSet::COMMON)?, None);
+    /// assert_eq!(Cell { buf: b"7" }.parse_nullable::<u32>(&NullSet::COMMON)?, Some(7));
+    /// // A quoted empty string is not null, so it fails to parse as a number.
+    /// assert!(Cell { buf: b"\"\"" }.parse_nullable::<u32>(&NullSet::COMMON).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_nullable<T: FromStr>(
+        &self,
+        nulls: &NullSet<'_>,
+    ) -> Result<Option<T>, CellParseError<T::Err>> {
+        if self.is_null_in(nulls) {
+            return Ok(None);
+        }
+        self.parse().map(Some)
+    }
+}
+
+/// Errors returned by [`Cell::parse()`] and [`Cell::parse_nullable()`].
+#[cfg(feature = "alloc")]
+#[derive(Error, Clone, Eq, PartialEq, Debug)]
+pub enum CellParseError<E> {
+    /// The cell is not valid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] core::str::Utf8Error),
+
+    /// The cell failed to parse as the requested type.
+    #[error("failed to parse cell: {0}")]
+    Parse(E),
 }
@@ -0,0 +1,206 @@
+//! Resource limits for parsing untrusted input.
+//!
+//! [`CsvLimitedIter`] yields the same items as [`Csv`], but fails with a [`LimitError`] as soon
+//! as the input exceeds one of the configured [`Limits`]. Each cell is searched for within a
+//! window bounded by the remaining cell and record limits, so a stray quote can't make the
+//! parser scan to the end of a large input.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{
+//!     Csv,
+//!     limit::{LimitError, Limits},
+//! };
+//!
+//! let limits = Limits {
+//!     max_cell_bytes: 8,
+//!     ..Default::default()
+//! };
+//! let mut items = Csv::new(b"a,b\n\"unterminated,c\n").into_limited(limits);
+//! assert!(items.by_ref().take(3).all(|item| item.is_ok()));
+//! assert_eq!(
+//!     items.next(),
+//!     Some(Err(LimitError::CellTooLong {
+//!         position: 4,
+//!         limit: 8,
+//!     })),
+//! );
+//! assert_eq!(items.next(), None);
+//! ```
+
+use thiserror::Error;
+
+use crate::{Csv, CsvIterItem, IterState};
+
+/// Limits enforced by [`CsvLimitedIter`].
+///
+/// Every limit defaults to [`usize::MAX`], i.e. unlimited.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Limits {
+    /// The maximum length of a cell in bytes, including the surrounding quotes.
+    pub max_cell_bytes: usize,
+    /// The maximum length of a record in bytes, excluding its line break.
+    pub max_record_bytes: usize,
+    /// The maximum number of cells in a record.
+    pub max_columns: usize,
+    /// The maximum number of records, including the header row.
+    pub max_records: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_cell_bytes: usize::MAX,
+            max_record_bytes: usize::MAX,
+            max_columns: usize::MAX,
+            max_records: usize::MAX,
+        }
+    }
+}
+
+/// An iterator that yields items until the input exceeds one of the [`Limits`].
+///
+/// Can be created by calling [`Csv::into_limited()`]. See the [module-level documentation](self)
+/// for more details.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CsvLimitedIter<'a> {
+    csv: Csv<'a>,
+    limits: Limits,
+    /// Position of the current record.
+    record_start: usize,
+    /// Number of cells read in the current record.
+    columns: usize,
+    /// Number of records read so far.
+    records: usize,
+}
+
+impl<'a> CsvLimitedIter<'a> {
+    pub(crate) fn new(csv: Csv<'a>, limits: Limits) -> Self {
+        Self {
+            record_start: csv.position(),
+            csv,
+            limits,
+            columns: 0,
+            records: 0,
+        }
+    }
+
+    /// Stops the iteration with the given error.
+    fn fail(&mut self, error: LimitError) -> Option<Result<CsvIterItem<'a>, LimitError>> {
+        self.csv.state = IterState::Done;
+        Some(Err(error))
+    }
+}
+
+impl<'a> Iterator for CsvLimitedIter<'a> {
+    type Item = Result<CsvIterItem<'a>, LimitError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = match self.csv.state {
+            IterState::Cell(start) => start,
+            IterState::LineEnd(..) => {
+                let item = self.csv.next();
+                self.record_start = self.csv.position();
+                self.columns = 0;
+                return item.map(Ok);
+            }
+            IterState::Done => return None,
+        };
+
+        // Only search the cell within the bytes it may span, allowing for a trailing CRLF.
+        let record_room = self
+            .limits
+            .max_record_bytes
+            .saturating_sub(start - self.record_start);
+        let limit = self.limits.max_cell_bytes.min(record_room);
+        let buf = self.csv.buf;
+        let cut = start.saturating_add(limit).saturating_add(2).min(buf.len());
+        let mut window = Csv {
+            buf: &buf[..cut],
+            ..self.csv
+        };
+        let item = window.next();
+        // Without a following separator or line break, the cell may continue past the window.
+        let truncated = cut < buf.len() && window.state == IterState::Done;
+
+        let len = match &item {
+            _ if truncated => cut - start,
+            Some(CsvIterItem::Cell(cell)) => cell.buf.len(),
+            _ => return None,
+        };
+        if len > limit {
+            return self.fail(if limit == self.limits.max_cell_bytes {
+                LimitError::CellTooLong {
+                    position: start,
+                    limit,
+                }
+            } else {
+                LimitError::RecordTooLong {
+                    position: self.record_start,
+                    limit: self.limits.max_record_bytes,
+                }
+            });
+        }
+
+        if self.columns == 0 {
+            self.records += 1;
+            if self.records > self.limits.max_records {
+                return self.fail(LimitError::TooManyRecords {
+                    position: start,
+                    limit: self.limits.max_records,
+                });
+            }
+        }
+        self.columns += 1;
+        if self.columns > self.limits.max_columns {
+            return self.fail(LimitError::TooManyColumns {
+                position: self.record_start,
+                limit: self.limits.max_columns,
+            });
+        }
+
+        self.csv.state = window.state;
+        item.map(Ok)
+    }
+}
+
+/// Errors returned by [`CsvLimitedIter`].
+#[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum LimitError {
+    /// A cell is longer than [`Limits::max_cell_bytes`].
+    #[error("cell at byte {position} exceeds the limit of {limit} bytes")]
+    CellTooLong {
+        /// Byte position of the cell.
+        position: usize,
+        /// The exceeded limit.
+        limit: usize,
+    },
+
+    /// A record is longer than [`Limits::max_record_bytes`].
+    #[error("record at byte {position} exceeds the limit of {limit} bytes")]
+    RecordTooLong {
+        /// Byte position of the record.
+        position: usize,
+        /// The exceeded limit.
+        limit: usize,
+    },
+
+    /// A record has more cells than [`Limits::max_columns`].
+    #[error("record at byte {position} exceeds the limit of {limit} columns")]
+    TooManyColumns {
+        /// Byte position of the record.
+        position: usize,
+        /// The exceeded limit.
+        limit: usize,
+    },
+
+    /// The input has more records than [`Limits::max_records`].
+    #[error("record at byte {position} exceeds the limit of {limit} records")]
+    TooManyRecords {
+        /// Byte position of the first record over the limit.
+        position: usize,
+        /// The exceeded limit.
+        limit: usize,
+    },
+}
@@ -0,0 +1,237 @@
+//! CSV parsing over owned, reference-counted buffers.
+//!
+//! Cells yielded by [`Csv`] borrow the buffer, so they can't outlive it or be moved to another
+//! thread on their own. [`OwnedCsv`] instead holds a cheaply clonable buffer such as
+//! [`Arc<[u8]>`](Arc) or `bytes::Bytes`, and yields [`OwnedCell`]s that each hold a clone of the
+//! buffer along with the byte range of the cell. Cells are therefore `'static`, and can be sent to
+//! worker threads without copying their contents.
+//!
+//! # Example
+//!
+//! ```
+//! use std::{sync::Arc, thread};
+//!
+//! use lazycsv::owned::OwnedCsv;
+//!
+//! let buf: Arc<[u8]> = Arc::from(&b"a,1\nb,2\n"[..]);
+//! let workers: Vec<_> = OwnedCsv::new(buf)
+//!     .into_rows()
+//!     .map(|row| {
+//!         let [name, value] = row.unwrap();
+//!         thread::spawn(move || (name.as_bytes().to_vec(), value.as_bytes().to_vec()))
+//!     })
+//!     .collect();
+//! let rows: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+//! assert_eq!(rows[1], (b"b".to_vec(), b"2".to_vec()));
+//! ```
+
+use alloc::sync::Arc;
+use core::{
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+
+use crate::{Cell, Csv, CsvIterItem, IterState, RowIterError};
+
+/// A stateful CSV parser over an owned buffer.
+///
+/// `B` is the buffer type, which should be cheap to clone, e.g. [`Arc<[u8]>`](Arc) (the default)
+/// or `bytes::Bytes`. See the [module-level documentation](self) for more details.
+#[derive(Clone, Debug)]
+pub struct OwnedCsv<B = Arc<[u8]>> {
+    buf: B,
+    separator: u8,
+    state: IterState,
+    back_cell: bool,
+}
+
+impl<B: Clone + AsRef<[u8]>> OwnedCsv<B> {
+    /// Creates a new CSV parser for the given buffer.
+    ///
+    /// To customize the separator character, use [`OwnedCsv::with_separator()`].
+    pub fn new(buf: B) -> Self {
+        Self::with_separator(buf, b',')
+    }
+
+    /// Creates a new CSV parser for the given buffer, with the given separator character.
+    pub fn with_separator(buf: B, separator: u8) -> Self {
+        let csv = Csv::with_separator(buf.as_ref(), separator);
+        Self {
+            state: csv.state,
+            back_cell: csv.back_cell,
+            buf,
+            separator,
+        }
+    }
+
+    /// Returns the underlying buffer.
+    pub fn buffer(&self) -> &B {
+        &self.buf
+    }
+
+    /// Returns a borrowing parser at the current position, e.g. to use the iterators of [`Csv`].
+    pub fn as_csv(&self) -> Csv<'_> {
+        Csv {
+            buf: self.buf.as_ref(),
+            separator: self.separator,
+            state: self.state,
+            back_cell: self.back_cell,
+        }
+    }
+
+    /// Create a wrapper iterator that buffers and yields rows of cells.
+    ///
+    /// This is the owned counterpart of [`Csv::into_rows()`].
+    pub fn into_rows<const COLS: usize>(self) -> OwnedCsvRowIter<B, COLS> {
+        OwnedCsvRowIter { csv: self }
+    }
+
+    /// Skips the first `n` rows.
+    ///
+    /// See [`Csv::skip_rows()`] for details.
+    pub fn skip_rows(mut self, n: usize) -> Self {
+        self.state = self.as_csv().skip_rows(n).state;
+        self
+    }
+
+    /// Returns the current byte position of the parser within the input buffer.
+    pub fn position(&self) -> usize {
+        self.as_csv().position()
+    }
+
+    /// Runs `f` on a borrowing parser, then advances to where it stopped.
+    fn advance<T>(&mut self, f: impl FnOnce(&mut Csv<'_>) -> T) -> T {
+        let mut csv = self.as_csv();
+        let result = f(&mut csv);
+        (self.state, self.back_cell) = (csv.state, csv.back_cell);
+        result
+    }
+
+    fn cell(&self, range: Range<usize>) -> OwnedCell<B> {
+        OwnedCell {
+            buf: self.buf.clone(),
+            range,
+        }
+    }
+}
+
+/// An item yielded by [`OwnedCsv`], indicating either a cell or a line break.
+#[derive(Clone, Debug)]
+pub enum OwnedCsvIterItem<B = Arc<[u8]>> {
+    /// The row continues with a cell.
+    Cell(OwnedCell<B>),
+    /// The row ends with a line break.
+    LineEnd,
+}
+
+impl<B: AsRef<[u8]>> PartialEq for OwnedCsvIterItem<B> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Cell(a), Self::Cell(b)) => a == b,
+            (Self::LineEnd, Self::LineEnd) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<B: AsRef<[u8]>> Eq for OwnedCsvIterItem<B> {}
+
+impl<B: Clone + AsRef<[u8]>> Iterator for OwnedCsv<B> {
+    type Item = OwnedCsvIterItem<B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.advance(|csv| {
+            let position = Csv::position(csv);
+            csv.next().map(|item| match item {
+                CsvIterItem::Cell(cell) => Some(position..position + cell.buf.len()),
+                CsvIterItem::LineEnd => None,
+            })
+        })?;
+        Some(match item {
+            Some(range) => OwnedCsvIterItem::Cell(self.cell(range)),
+            None => OwnedCsvIterItem::LineEnd,
+        })
+    }
+}
+
+/// An iterator that buffers and yields rows of owned cells.
+///
+/// Can be created by calling [`OwnedCsv::into_rows()`].
+///
+/// ### `const` Parameters
+///
+/// - `COLS`: The number of columns in the CSV.
+#[derive(Clone, Debug)]
+pub struct OwnedCsvRowIter<B, const COLS: usize> {
+    csv: OwnedCsv<B>,
+}
+
+impl<B: Clone + AsRef<[u8]>, const COLS: usize> OwnedCsvRowIter<B, COLS> {
+    /// Skips the first `n` rows.
+    ///
+    /// Using this function is more efficient than calling [`Iterator::skip()`],
+    /// as it only looks for newline characters instead of trying to recognize cells.
+    pub fn skip(self, n: usize) -> Self {
+        Self {
+            csv: self.csv.skip_rows(n),
+        }
+    }
+}
+
+impl<B: Clone + AsRef<[u8]>, const COLS: usize> Iterator for OwnedCsvRowIter<B, COLS> {
+    type Item = Result<[OwnedCell<B>; COLS], RowIterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.csv.advance(|csv| {
+            csv.next_row::<_, COLS>(|cell, position| position..position + cell.buf.len())
+        })?;
+        Some(row.map(|ranges| ranges.map(|range| self.csv.cell(range))))
+    }
+}
+
+/// A cell holding a clone of the buffer it was parsed from.
+///
+/// Comparing and hashing only looks at the contents of the cell, as with [`Cell`].
+#[derive(Clone, Debug)]
+pub struct OwnedCell<B = Arc<[u8]>> {
+    buf: B,
+    range: Range<usize>,
+}
+
+impl<B: AsRef<[u8]>> OwnedCell<B> {
+    /// Returns the cell borrowing the buffer, e.g. to call [`Cell::try_as_str()`].
+    pub fn as_cell(&self) -> Cell<'_> {
+        Cell {
+            buf: self.as_bytes(),
+        }
+    }
+
+    /// Returns the potentially quoted cell content as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf.as_ref()[self.range.clone()]
+    }
+
+    /// Returns the byte position range of the cell within the buffer.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the buffer the cell was parsed from.
+    pub fn buffer(&self) -> &B {
+        &self.buf
+    }
+}
+
+impl<B: AsRef<[u8]>> PartialEq for OwnedCell<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<B: AsRef<[u8]>> Eq for OwnedCell<B> {}
+
+impl<B: AsRef<[u8]>> Hash for OwnedCell<B> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_cell().hash(state);
+    }
+}
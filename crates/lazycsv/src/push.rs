@@ -0,0 +1,223 @@
+//! Push-based incremental parser, for input that arrives in arbitrary-sized chunks.
+//!
+//! [`PushParser`] is useful when the input can't be pulled from a reader, e.g. when receiving CSV
+//! over a socket. Each chunk is passed to [`PushParser::feed()`], which emits the
+//! same [`CsvIterItem`]s as [`Csv`](crate::Csv) to a callback. Cells that are entirely within a
+//! chunk are borrowed from it, and only a cell spanning multiple chunks is copied to a carry
+//! buffer of fixed size, so the parser doesn't allocate and can be used in `no_std` environments.
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::{CsvIterItem, push::PushParser};
+//!
+//! let mut parser = PushParser::<64>::new();
+//! let mut cells = 0;
+//! let mut lines = 0;
+//! let mut on_item = |item: CsvIterItem<'_>| match item {
+//!     CsvIterItem::Cell(_) => cells += 1,
+//!     CsvIterItem::LineEnd => lines += 1,
+//! };
+//! for chunk in [&b"a,\"b"[..], b"\nc\"\r", b"\n1,2"] {
+//!     parser.feed(chunk, &mut on_item)?;
+//! }
+//! parser.finish(&mut on_item)?;
+//! assert_eq!((cells, lines), (4, 1));
+//! # Ok::<(), lazycsv::push::PushError>(())
+//! ```
+
+use memchr::{memchr, memchr3};
+use thiserror::Error;
+
+use crate::{Cell, CsvIterItem};
+
+/// A resumable CSV parser that is fed with chunks of input.
+///
+/// See the [module-level documentation](self) for more details.
+///
+/// ### `const` Parameters
+///
+/// - `CARRY`: The capacity of the carry buffer, which limits the length of a cell spanning
+///   multiple chunks.
+#[derive(Clone, Debug)]
+pub struct PushParser<const CARRY: usize> {
+    separator: u8,
+    carry: [u8; CARRY],
+    carry_len: usize,
+    /// Whether the end of the input so far is inside quotes.
+    in_quoted_state: bool,
+    /// Whether a cell has been started (possibly with no content yet) but not emitted.
+    in_cell: bool,
+    /// Whether the current cell overflowed the carry buffer, so it must be skipped.
+    overflowed: bool,
+}
+
+impl<const CARRY: usize> Default for PushParser<CARRY> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CARRY: usize> PushParser<CARRY> {
+    /// Creates a new push parser.
+    ///
+    /// To customize the separator character, use [`PushParser::with_separator()`].
+    pub fn new() -> Self {
+        Self::with_separator(b',')
+    }
+
+    /// Creates a new push parser with the given separator character.
+    pub fn with_separator(separator: u8) -> Self {
+        Self {
+            separator,
+            carry: [0; CARRY],
+            carry_len: 0,
+            in_quoted_state: false,
+            in_cell: false,
+            overflowed: false,
+        }
+    }
+
+    /// Parses the next chunk of input, calling `f` with each complete item.
+    ///
+    /// The last cell of the chunk is held back until a separator or a line break is fed, or
+    /// [`PushParser::finish()`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PushError::CarryOverflow`] if a cell spanning chunks doesn't fit in the carry
+    /// buffer. Such a cell is skipped, while the other items in the chunk are still emitted.
+    pub fn feed(
+        &mut self,
+        chunk: &[u8],
+        mut f: impl FnMut(CsvIterItem<'_>),
+    ) -> Result<(), PushError> {
+        let mut start = 0;
+        let mut cursor = 0;
+        let mut result = Ok(());
+
+        loop {
+            if self.in_quoted_state {
+                let Some(index_relative) = memchr(b'"', &chunk[cursor..]) else {
+                    break;
+                };
+                self.in_quoted_state = false;
+                cursor += index_relative + 1;
+                continue;
+            }
+
+            let Some(index_relative) = memchr3(self.separator, b'\n', b'"', &chunk[cursor..])
+            else {
+                break;
+            };
+            let index = index_relative + cursor;
+            let c = chunk[index];
+
+            if c == b'"' {
+                self.in_quoted_state = true;
+                cursor = index + 1;
+                continue;
+            }
+
+            let mut end = index;
+            if c == b'\n' {
+                // Strip CR of CRLF, which may have been carried over from the previous chunk.
+                if end > start && chunk[end - 1] == b'\r' {
+                    end -= 1;
+                } else if end == start && self.carry[..self.carry_len].ends_with(b"\r") {
+                    self.carry_len -= 1;
+                }
+            }
+
+            if self.overflowed {
+                self.overflowed = false;
+            } else if self.carry_len == 0 {
+                f(CsvIterItem::Cell(Cell {
+                    buf: &chunk[start..end],
+                }));
+            } else {
+                match self.carry(&chunk[start..end]) {
+                    Ok(()) => f(CsvIterItem::Cell(Cell {
+                        buf: &self.carry[..self.carry_len],
+                    })),
+                    Err(err) => result = Err(err),
+                }
+                self.carry_len = 0;
+            }
+
+            if c == b'\n' {
+                f(CsvIterItem::LineEnd);
+                self.in_cell = false;
+            } else {
+                self.in_cell = true;
+            }
+            start = index + 1;
+            cursor = start;
+        }
+
+        if start < chunk.len() {
+            self.in_cell = true;
+            if !self.overflowed
+                && let Err(err) = self.carry(&chunk[start..])
+            {
+                self.carry_len = 0;
+                self.overflowed = true;
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// Finishes the input, calling `f` with the last cell if there is one, and resets the parser
+    /// so that it can be reused for another input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PushError::UnterminatedQuote`] if the input ends inside quotes. In this case, the
+    /// last cell is discarded.
+    pub fn finish(&mut self, mut f: impl FnMut(CsvIterItem<'_>)) -> Result<(), PushError> {
+        let result = if self.in_quoted_state {
+            Err(PushError::UnterminatedQuote)
+        } else {
+            if self.in_cell && !self.overflowed {
+                f(CsvIterItem::Cell(Cell {
+                    buf: &self.carry[..self.carry_len],
+                }));
+            }
+            Ok(())
+        };
+        self.carry_len = 0;
+        self.in_quoted_state = false;
+        self.in_cell = false;
+        self.overflowed = false;
+        result
+    }
+
+    /// Appends a part of a cell to the carry buffer.
+    fn carry(&mut self, part: &[u8]) -> Result<(), PushError> {
+        let Some(dest) = self
+            .carry
+            .get_mut(self.carry_len..self.carry_len + part.len())
+        else {
+            return Err(PushError::CarryOverflow { capacity: CARRY });
+        };
+        dest.copy_from_slice(part);
+        self.carry_len += part.len();
+        Ok(())
+    }
+}
+
+/// Errors returned by [`PushParser`].
+#[derive(Error, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PushError {
+    /// A cell spanning multiple chunks is longer than the carry buffer.
+    #[error("cell spanning multiple chunks exceeds the carry buffer capacity of {capacity} bytes")]
+    CarryOverflow {
+        /// The capacity of the carry buffer.
+        capacity: usize,
+    },
+
+    /// The input ended inside quotes.
+    #[error("input ended inside a quoted cell")]
+    UnterminatedQuote,
+}
@@ -0,0 +1,88 @@
+//! Asynchronous record stream for [`tokio::io::AsyncRead`].
+//!
+//! [`AsyncCsvReader`] reads the input into an internal buffer and splits it with the same parser
+//! as [`Csv`](crate::Csv), yielding each complete record as an owned [`Record`]. Records split
+//! across reads are held back until their line break (or the end of the input) arrives.
+//!
+//! # Example
+//!
+//! ```
+//! use futures::TryStreamExt;
+//! use lazycsv::tokio::AsyncCsvReader;
+//!
+//! # futures::executor::block_on(async {
+//! let input: &[u8] = b"isrc,units\nUSRC17607839,3\n";
+//! let mut records = AsyncCsvReader::new(input);
+//! while let Some(record) = records.try_next().await? {
+//!     let [isrc, units] = record.to_array()?;
+//!     println!("{}: {}", isrc.try_as_str()?, units.try_as_str()?);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # });
+//! ```
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use std::io;
+
+use ::tokio::io::{AsyncRead, ReadBuf};
+use futures_core::Stream;
+
+pub use crate::io::Record;
+use crate::io::RecordBuffer;
+
+/// An asynchronous stream of records read from [`AsyncRead`].
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug)]
+pub struct AsyncCsvReader<R> {
+    reader: R,
+    buf: RecordBuffer,
+}
+
+impl<R: AsyncRead + Unpin> AsyncCsvReader<R> {
+    /// Creates a new record stream reading from `reader`.
+    ///
+    /// To customize the separator character, use [`AsyncCsvReader::with_separator()`].
+    pub fn new(reader: R) -> Self {
+        Self::with_separator(reader, b',')
+    }
+
+    /// Creates a new record stream reading from `reader`, with the given separator character.
+    pub fn with_separator(reader: R, separator: u8) -> Self {
+        Self {
+            reader,
+            buf: RecordBuffer::new(separator),
+        }
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Data that has been read but not yielded as records yet is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for AsyncCsvReader<R> {
+    type Item = io::Result<Record>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(record) = this.buf.parse_record() {
+                return Poll::Ready(Some(record));
+            }
+            if this.buf.eof {
+                return Poll::Ready(None);
+            }
+
+            let mut read_buf = ReadBuf::new(this.buf.spare());
+            ready!(Pin::new(&mut this.reader).poll_read(cx, &mut read_buf))?;
+            let n = read_buf.filled().len();
+            this.buf.fill(n);
+        }
+    }
+}
@@ -0,0 +1,630 @@
+//! Parsing rows into typed values.
+//!
+//! [`FromCell`] converts a single [`Cell`] into a value, and [`FromRow`] converts a whole row.
+//! `FromRow` is implemented for tuples of up to 12 `FromCell` types, so that simple scripts can
+//! read typed rows with [`Csv::into_typed_rows()`] without pulling in serde. With the **derive**
+//! feature, `FromRow` can also be derived for structs, whose fields are mapped to columns by
+//! position or by header name with [`Csv::into_typed_rows_by_header()`].
+//!
+//! Cells are dequoted before parsing. Borrowed types such as `&str` are zero-copy, and fail with
+//! [`FromCellError::EscapedQuote`] on cells containing escaped quotes, which can only be
+//! represented by an owned `String` or `Cow<str>` (with the **alloc** feature).
+//!
+//! # Example
+//!
+//! ```
+//! use lazycsv::Csv;
+//!
+//! let csv = Csv::new(b"isrc,title,price,units\nUSRC17607839,\"Song, A\",1.5,\n");
+//! for row in csv.skip_rows(1).into_typed_rows::<(&str, &str, f64, Option<u64>)>() {
+//!     let (isrc, title, price, units) = row?;
+//!     assert_eq!((isrc, title, price, units), ("USRC17607839", "Song, A", 1.5, None));
+//! }
+//! # Ok::<(), lazycsv::typed::TypedRowError>(())
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    char::ParseCharError,
+    marker::PhantomData,
+    num::{ParseFloatError, ParseIntError},
+    str::{ParseBoolError, Utf8Error},
+};
+
+use memchr::memchr;
+use thiserror::Error;
+
+use crate::{Cell, Csv, CsvIterItem, RowIterError};
+
+/// Derives [`FromRow`] for a struct, parsing each field from a cell with [`FromCell`].
+///
+/// Fields are read in declaration order, so [`Csv::into_typed_rows()`] maps them to columns by
+/// position, and [`FromRow::COLS`] is the number of fields. For structs with named fields,
+/// [`Csv::into_typed_rows_by_header()`] maps them to columns by header names instead, which
+/// default to the field names.
+///
+/// The struct may borrow cells for its first lifetime parameter, e.g. with `&'a str` fields.
+///
+/// # Field attributes
+///
+/// - `#[csv(name = "...")]`: The header name of the column, for structs with named fields.
+/// - `#[csv(with = "path")]`: Parses the cell with a function of type
+///   `fn(Cell<'a>) -> Result<T, FromCellError>` instead of [`FromCell`].
+///
+/// Fields of type `Option<T>` are optional: their column may be missing from the header, in which
+/// case they're always `None`.
+///
+/// # Example
+///
+/// ```
+/// use lazycsv::{
+///     Cell, Csv,
+///     typed::{FromCellError, FromRow},
+/// };
+///
+/// #[derive(FromRow)]
+/// struct Song<'a> {
+///     #[csv(name = "ISRC")]
+///     isrc: &'a str,
+///     #[csv(name = "Title")]
+///     title: &'a str,
+///     #[csv(name = "Price", with = "parse_cents")]
+///     cents: u64,
+///     #[csv(name = "Units")]
+///     units: Option<u64>,
+/// }
+///
+/// fn parse_cents(cell: Cell<'_>) -> Result<u64, FromCellError> {
+///     let price: f64 = lazycsv::typed::FromCell::from_cell(cell)?;
+///     Ok((price * 100.0).round() as u64)
+/// }
+///
+/// let csv = Csv::new(b"Title,ISRC,Price\n\"Song, A\",USRC17607839,1.5\n");
+/// for song in csv.into_typed_rows_by_header::<Song>()? {
+///     let song = song?;
+///     assert_eq!((song.isrc, song.title), ("USRC17607839", "Song, A"));
+///     assert_eq!((song.cents, song.units), (150, None));
+/// }
+///
+/// // The column count is known at compile time, so rows can also be read with `CsvRowIter`.
+/// let csv = Csv::new(b"USRC17607839,Song A,1.5,3\n");
+/// for row in csv.into_rows_with_range::<{ <Song as FromRow>::COLS }>() {
+///     let (cells, range) = row?;
+///     let song = Song::from_cells(&cells, range.start)?;
+///     assert_eq!(song.units, Some(3));
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "derive")]
+pub use lazycsv_derive::FromRow;
+
+/// Types that can be parsed from a single cell.
+///
+/// # Example
+///
+/// ```
+/// use lazycsv::{
+///     Cell,
+///     typed::{FromCell, FromCellError},
+/// };
+///
+/// struct Isrc<'a>(&'a str);
+///
+/// impl<'a> FromCell<'a> for Isrc<'a> {
+///     fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+///         match <&str>::from_cell(cell)? {
+///             s if s.len() == 12 => Ok(Isrc(s)),
+///             _ => Err(FromCellError::Invalid { expected: "ISRC" }),
+///         }
+///     }
+/// }
+/// ```
+pub trait FromCell<'a>: Sized {
+    /// Parses the cell.
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError>;
+}
+
+/// Types that can be parsed from a row of cells.
+///
+/// Implementations read the cells in order with [`RowCells::next_cell()`], which takes care of
+/// reporting missing cells and the column of a cell that failed to parse. With the **derive**
+/// feature, this can be derived for structs with `#[derive(FromRow)]`.
+pub trait FromRow<'a>: Sized {
+    /// The number of cells in a row.
+    ///
+    /// This is a constant, so it can be used as the `COLS` parameter of
+    /// [`CsvRowIter`](crate::CsvRowIter), e.g. `into_rows::<{ <Song as FromRow>::COLS }>()`.
+    const COLS: usize;
+
+    /// The header names of the fields in order, if rows can be mapped by header names with
+    /// [`Csv::into_typed_rows_by_header()`].
+    const HEADERS: Option<&'static [HeaderField]> = None;
+
+    /// Parses the row, reading exactly [`FromRow::COLS`] cells from `cells`.
+    fn from_row(cells: &mut RowCells<'_, 'a>) -> Result<Self, TypedRowError>;
+
+    /// Parses a row of cells yielded by another iterator, e.g. [`CsvRowIter`](crate::CsvRowIter).
+    ///
+    /// `position` is the byte position of the row, which is used to compute the position of the
+    /// cells for errors. `cells` should be a whole row as yielded by the row iterators, so that
+    /// each cell is followed by a single separator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lazycsv::{Csv, typed::FromRow};
+    ///
+    /// for row in Csv::new(b"1,a\n2,b\n").into_rows_with_range::<2>() {
+    ///     let (cells, range) = row?;
+    ///     let (n, s) = <(u32, &str)>::from_cells(&cells, range.start)?;
+    ///     println!("{n}: {s}");
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn from_cells(cells: &[Cell<'a>], position: usize) -> Result<Self, TypedRowError> {
+        let mut row = RowCells {
+            source: Source::Cells { cells, position },
+            columns: &[],
+            names: &[],
+            expected: Self::COLS,
+            field: 0,
+        };
+        let result = Self::from_row(&mut row);
+        check_column_count(result, cells.len(), Self::COLS)
+    }
+}
+
+/// The header name of a field of a [`FromRow`] type.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct HeaderField {
+    /// The header name of the column to read the field from.
+    pub name: &'static str,
+    /// Whether the column may be missing from the header, in which case the field is skipped with
+    /// [`RowCells::skip_missing()`].
+    pub optional: bool,
+}
+
+/// The cells of a row being parsed by [`FromRow::from_row()`].
+#[derive(Debug)]
+pub struct RowCells<'r, 'a> {
+    source: Source<'r, 'a>,
+    /// The column of each field, if mapped by header names.
+    columns: &'r [Option<usize>],
+    names: &'static [HeaderField],
+    expected: usize,
+    /// The index of the next field.
+    field: usize,
+}
+
+#[derive(Debug)]
+enum Source<'r, 'a> {
+    Csv {
+        csv: &'r mut Csv<'a>,
+        /// The parser at the start of the row, to go back to for columns read out of order.
+        row: Csv<'a>,
+        /// The index of the next cell.
+        column: usize,
+        /// Whether the line break or the end of the input has been reached.
+        ended: bool,
+    },
+    Cells {
+        cells: &'r [Cell<'a>],
+        position: usize,
+    },
+}
+
+impl<'a> RowCells<'_, 'a> {
+    /// Parses the cell of the next field as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TypedRowError::Row`] if the row has no more cells, [`TypedRowError::Cell`] if the
+    /// cell fails to parse, or [`TypedRowError::MissingColumn`] if the column of the field is
+    /// missing from the header.
+    pub fn next_cell<T: FromCell<'a>>(&mut self) -> Result<T, TypedRowError> {
+        self.next_cell_with(T::from_cell)
+    }
+
+    /// Parses the cell of the next field with a custom parser.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`RowCells::next_cell()`].
+    pub fn next_cell_with<T>(
+        &mut self,
+        parse: impl FnOnce(Cell<'a>) -> Result<T, FromCellError>,
+    ) -> Result<T, TypedRowError> {
+        let field = self.field;
+        self.field += 1;
+        let column = match self.columns.get(field) {
+            Some(Some(column)) => *column,
+            Some(None) => {
+                return Err(TypedRowError::MissingColumn {
+                    name: self.names[field].name,
+                });
+            }
+            None => field,
+        };
+
+        let (cell, position) = self.cell(column)?;
+        parse(cell).map_err(|source| TypedRowError::Cell {
+            column,
+            position,
+            source,
+        })
+    }
+
+    /// Skips the next field if its column is missing from the header, returning whether it was
+    /// skipped.
+    ///
+    /// Columns are only missing for fields marked as [`HeaderField::optional`], which should be
+    /// left empty in that case.
+    pub fn skip_missing(&mut self) -> bool {
+        let missing = matches!(self.columns.get(self.field), Some(None));
+        if missing {
+            self.field += 1;
+        }
+        missing
+    }
+
+    /// Returns the cell at `target` and its position.
+    fn cell(&mut self, target: usize) -> Result<(Cell<'a>, usize), TypedRowError> {
+        let error = |actual| {
+            TypedRowError::Row(RowIterError::ColumnCountSmallerThanExpected {
+                expected: self.expected,
+                actual,
+            })
+        };
+
+        match &mut self.source {
+            Source::Csv {
+                csv,
+                row,
+                column,
+                ended,
+            } => {
+                if target < *column {
+                    **csv = *row;
+                    *column = 0;
+                    *ended = false;
+                }
+                loop {
+                    let position = Csv::position(csv);
+                    let item = match ended {
+                        false => csv.next(),
+                        true => None,
+                    };
+                    let Some(CsvIterItem::Cell(cell)) = item else {
+                        *ended = true;
+                        return Err(error(*column));
+                    };
+                    *column += 1;
+                    if *column > target {
+                        return Ok((cell, position));
+                    }
+                }
+            }
+            Source::Cells { cells, position } => match cells.get(target) {
+                // Each preceding cell is followed by a single separator.
+                Some(cell) => Ok((
+                    *cell,
+                    cells[..target]
+                        .iter()
+                        .fold(*position, |offset, cell| offset + cell.buf.len() + 1),
+                )),
+                None => Err(error(cells.len())),
+            },
+        }
+    }
+
+    /// Skips the rest of the row, returning the number of cells in it.
+    fn finish(self) -> usize {
+        match self.source {
+            Source::Csv {
+                csv,
+                mut column,
+                ended,
+                ..
+            } => {
+                if !ended {
+                    while let Some(CsvIterItem::Cell(_)) = csv.next() {
+                        column += 1;
+                    }
+                }
+                column
+            }
+            Source::Cells { cells, .. } => cells.len(),
+        }
+    }
+}
+
+/// Fails a successfully parsed row if it has a different number of cells than expected.
+fn check_column_count<T>(
+    result: Result<T, TypedRowError>,
+    actual: usize,
+    expected: usize,
+) -> Result<T, TypedRowError> {
+    match result {
+        Ok(_) if actual > expected => Err(TypedRowError::Row(
+            RowIterError::ColumnCountLargerThanExpected { expected },
+        )),
+        Ok(_) if actual < expected => Err(TypedRowError::Row(
+            RowIterError::ColumnCountSmallerThanExpected { expected, actual },
+        )),
+        result => result,
+    }
+}
+
+/// An iterator that parses each row into `T`.
+///
+/// Can be created by calling [`Csv::into_typed_rows()`] or [`Csv::into_typed_rows_by_header()`].
+#[derive(Debug)]
+pub struct CsvTypedRowIter<'a, T> {
+    csv: Csv<'a>,
+    /// The column of each field, if mapped by header names.
+    #[cfg(feature = "alloc")]
+    columns: Vec<Option<usize>>,
+    expected: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for CsvTypedRowIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            csv: self.csv,
+            #[cfg(feature = "alloc")]
+            columns: self.columns.clone(),
+            expected: self.expected,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: FromRow<'a>> CsvTypedRowIter<'a, T> {
+    pub(crate) fn new(csv: Csv<'a>) -> Self {
+        Self {
+            csv,
+            #[cfg(feature = "alloc")]
+            columns: Vec::new(),
+            expected: T::COLS,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads the header row, mapping the fields of `T` to columns by [`FromRow::HEADERS`].
+    #[cfg(feature = "alloc")]
+    pub(crate) fn with_header(mut csv: Csv<'a>) -> Result<Self, TypedRowError> {
+        let mut header = Vec::new();
+        loop {
+            let position = Csv::position(&csv);
+            let Some(CsvIterItem::Cell(cell)) = csv.next() else {
+                break;
+            };
+            let name = cell.try_as_str().map_err(|source| TypedRowError::Cell {
+                column: header.len(),
+                position,
+                source: source.into(),
+            })?;
+            header.push(name);
+        }
+
+        let Some(fields) = T::HEADERS else {
+            return Ok(Self::new(csv));
+        };
+        let columns = fields
+            .iter()
+            .map(|field| {
+                let column = header.iter().position(|name| name == field.name);
+                match column {
+                    None if !field.optional => {
+                        Err(TypedRowError::MissingColumn { name: field.name })
+                    }
+                    column => Ok(column),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            csv,
+            columns,
+            expected: header.len(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Skips the first `n` rows.
+    ///
+    /// Using this function is more efficient than calling [`Iterator::skip()`],
+    /// as it only looks for newline characters instead of trying to recognize cells.
+    pub fn skip(mut self, n: usize) -> Self {
+        self.csv = self.csv.skip_rows(n);
+        self
+    }
+}
+
+impl<'a, T: FromRow<'a>> Iterator for CsvTypedRowIter<'a, T> {
+    type Item = Result<T, TypedRowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // If we reach EOF before reading any cells, there are no more rows available.
+        let mut peek = self.csv;
+        peek.next()?;
+
+        #[cfg(feature = "alloc")]
+        let columns = &self.columns[..];
+        #[cfg(not(feature = "alloc"))]
+        let columns = &[];
+        let mut cells = RowCells {
+            source: Source::Csv {
+                row: self.csv,
+                csv: &mut self.csv,
+                column: 0,
+                ended: false,
+            },
+            columns,
+            names: T::HEADERS.unwrap_or_default(),
+            expected: self.expected,
+            field: 0,
+        };
+        let result = T::from_row(&mut cells);
+        let actual = cells.finish();
+        Some(check_column_count(result, actual, self.expected))
+    }
+}
+
+/// Errors returned by [`FromCell::from_cell()`].
+#[derive(Error, Clone, Eq, PartialEq, Debug)]
+pub enum FromCellError {
+    /// The cell is not valid UTF-8.
+    #[error(transparent)]
+    InvalidUtf8(#[from] Utf8Error),
+
+    /// The cell contains escaped quotes, so it can't be borrowed without dequoting.
+    #[error("cell contains escaped quotes, which requires an owned string")]
+    EscapedQuote,
+
+    /// The cell is not a valid integer.
+    #[error(transparent)]
+    InvalidInt(#[from] ParseIntError),
+
+    /// The cell is not a valid floating point number.
+    #[error(transparent)]
+    InvalidFloat(#[from] ParseFloatError),
+
+    /// The cell is not `true` or `false`.
+    #[error(transparent)]
+    InvalidBool(#[from] ParseBoolError),
+
+    /// The cell is not a single character.
+    #[error(transparent)]
+    InvalidChar(#[from] ParseCharError),
+
+    /// The cell is not valid for a custom type.
+    #[error("cell is not a valid {expected}")]
+    Invalid {
+        /// A description of the expected value.
+        expected: &'static str,
+    },
+}
+
+/// Errors returned by [`CsvTypedRowIter`].
+#[derive(Error, Clone, Eq, PartialEq, Debug)]
+pub enum TypedRowError {
+    /// The row has a different number of cells than expected.
+    #[error(transparent)]
+    Row(#[from] RowIterError),
+
+    /// A column of a field is missing from the header.
+    #[error("missing column {name:?}")]
+    MissingColumn {
+        /// The header name of the column.
+        name: &'static str,
+    },
+
+    /// A cell failed to parse.
+    #[error("column {column} at byte {position}: {source}")]
+    Cell {
+        /// The index of the cell.
+        column: usize,
+        /// Byte position of the cell.
+        position: usize,
+        /// The parse error.
+        #[source]
+        source: FromCellError,
+    },
+}
+
+/// Strips the surrounding quotes, failing if the content has escaped quotes.
+fn unquote(cell: Cell<'_>) -> Result<&[u8], FromCellError> {
+    let content = match cell.buf {
+        [b'"', content @ .., b'"'] => content,
+        buf => return Ok(buf),
+    };
+    match memchr(b'"', content) {
+        Some(_) => Err(FromCellError::EscapedQuote),
+        None => Ok(content),
+    }
+}
+
+impl<'a> FromCell<'a> for Cell<'a> {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        Ok(cell)
+    }
+}
+
+impl<'a> FromCell<'a> for &'a [u8] {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        unquote(cell)
+    }
+}
+
+impl<'a> FromCell<'a> for &'a str {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        Ok(core::str::from_utf8(unquote(cell)?)?)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> FromCell<'a> for Cow<'a, str> {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        Ok(cell.try_as_str()?)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> FromCell<'a> for String {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        Ok(cell.try_as_str()?.into_owned())
+    }
+}
+
+/// Null if the cell is empty and unquoted, as in [`Cell::is_null()`].
+impl<'a, T: FromCell<'a>> FromCell<'a> for Option<T> {
+    fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+        if cell.is_null() {
+            return Ok(None);
+        }
+        T::from_cell(cell).map(Some)
+    }
+}
+
+macro_rules! impl_from_cell_parse {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a> FromCell<'a> for $ty {
+                fn from_cell(cell: Cell<'a>) -> Result<Self, FromCellError> {
+                    Ok(<&str>::from_cell(cell)?.parse()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_cell_parse!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
+);
+
+macro_rules! impl_from_row_tuple {
+    ($cols:literal: $($ty:ident),*) => {
+        impl<'a, $($ty: FromCell<'a>),*> FromRow<'a> for ($($ty,)*) {
+            const COLS: usize = $cols;
+
+            fn from_row(cells: &mut RowCells<'_, 'a>) -> Result<Self, TypedRowError> {
+                Ok(($(cells.next_cell::<$ty>()?,)*))
+            }
+        }
+    };
+}
+
+impl_from_row_tuple!(1: A);
+impl_from_row_tuple!(2: A, B);
+impl_from_row_tuple!(3: A, B, C);
+impl_from_row_tuple!(4: A, B, C, D);
+impl_from_row_tuple!(5: A, B, C, D, E);
+impl_from_row_tuple!(6: A, B, C, D, E, F);
+impl_from_row_tuple!(7: A, B, C, D, E, F, G);
+impl_from_row_tuple!(8: A, B, C, D, E, F, G, H);
+impl_from_row_tuple!(9: A, B, C, D, E, F, G, H, I);
+impl_from_row_tuple!(10: A, B, C, D, E, F, G, H, I, J);
+impl_from_row_tuple!(11: A, B, C, D, E, F, G, H, I, J, K);
+impl_from_row_tuple!(12: A, B, C, D, E, F, G, H, I, J, K, L);
@@ -68,6 +68,62 @@ fn dequote() {
     assert_eq!(cell.try_as_str().unwrap(), r#"Hi "Quote" yo"#);
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn null_values() {
+    use lazycsv::{CellParseError, NullSet};
+
+    let csv = Csv::new(b"1,,\"\",NULL,null,\"NULL\",\\N,N/A,x");
+    let cells: Vec<_> = csv
+        .filter_map(|item| match item {
+            CsvIterItem::Cell(cell) => Some(cell),
+            CsvIterItem::LineEnd => None,
+        })
+        .collect();
+
+    let nulls =
+        |set: &NullSet<'_>| -> Vec<bool> { cells.iter().map(|c| c.is_null_in(set)).collect() };
+    assert_eq!(
+        cells.iter().map(Cell::is_null).collect::<Vec<_>>(),
+        [false, true, false, false, false, false, false, false, false]
+    );
+    assert_eq!(nulls(&NullSet::default()), nulls(&NullSet::EMPTY));
+    assert_eq!(
+        nulls(&NullSet::EMPTY),
+        [false, true, false, false, false, false, false, false, false]
+    );
+    assert_eq!(
+        nulls(&NullSet::COMMON),
+        [false, true, false, true, false, false, true, true, false]
+    );
+    let custom = NullSet {
+        values: &[b"null"],
+        ignore_ascii_case: true,
+    };
+    assert_eq!(
+        nulls(&custom),
+        [false, false, false, true, true, false, false, false, false]
+    );
+
+    let parsed: Vec<_> = cells
+        .iter()
+        .map(|c| c.parse_nullable::<u32>(&NullSet::COMMON))
+        .collect();
+    assert_eq!(parsed[0], Ok(Some(1)));
+    assert_eq!(parsed[1], Ok(None));
+    assert!(matches!(parsed[2], Err(CellParseError::Parse(_))));
+    assert_eq!(parsed[3], Ok(None));
+    assert!(matches!(parsed[8], Err(CellParseError::Parse(_))));
+    assert_eq!(
+        cells[5].parse_nullable::<String>(&NullSet::COMMON),
+        Ok(Some("NULL".to_string()))
+    );
+    assert!(matches!(
+        Cell { buf: b"\xff" }.parse::<u32>(),
+        Err(CellParseError::InvalidUtf8(_))
+    ));
+}
+
 #[test]
 fn check_corner_cases() {
     // No trailing newline
@@ -238,3 +294,1428 @@ fn into_rows_with_range_no_trailing_newline() {
 
     assert!(iter.next().is_none());
 }
+
+#[test]
+fn rev_matches_forward() {
+    let inputs: &[&[u8]] = &[
+        b"",
+        b"\n",
+        b"\n\n",
+        b"a",
+        b",",
+        b",,\n",
+        b"a,b,c\n1,2,3\n4,5,6",
+        b"a,b,c\n1,2,3\n4,5,6\n",
+        b"aaa,bbb\r\n100,200\r\n",
+        b"aaa,\n100,",
+        b"a\n\nb\n",
+        br#""Hello, world!","Hi ""Quote""","""HELLO""","""name""""#,
+        b"\"multi\nline\",\"\"\r\n\"\",x\n",
+    ];
+
+    for input in inputs {
+        let forward: Vec<_> = Csv::new(input).collect();
+        let mut backward: Vec<_> = Csv::new(input).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward, "input: {:?}", input.escape_ascii());
+
+        // Consuming both ends alternately must yield every item exactly once.
+        for front_first in [true, false] {
+            let mut csv = Csv::new(input);
+            let (mut head, mut tail) = (Vec::new(), Vec::new());
+            let mut take_front = front_first;
+            loop {
+                let item = if take_front {
+                    csv.next().map(|item| head.push(item))
+                } else {
+                    csv.next_back().map(|item| tail.push(item))
+                };
+                if item.is_none() {
+                    break;
+                }
+                take_front = !take_front;
+            }
+            head.extend(csv.by_ref());
+            tail.extend(csv.by_ref().rev());
+            head.extend(tail.into_iter().rev());
+            assert_eq!(forward, head, "input: {:?}", input.escape_ascii());
+        }
+    }
+}
+
+#[test]
+fn rev_position() {
+    let mut csv = Csv::new(b"a,b\n1,2\n");
+    assert_csv!(csv, Cell(b"a"));
+    assert_eq!(csv.next_back(), Some(CsvIterItem::LineEnd));
+    assert_eq!(csv.next_back(), Some(CsvIterItem::Cell(Cell { buf: b"2" })));
+    assert_eq!(csv.next_back(), Some(CsvIterItem::Cell(Cell { buf: b"1" })));
+    assert_eq!(csv.next_back(), Some(CsvIterItem::LineEnd));
+    assert_eq!(csv.next_back(), Some(CsvIterItem::Cell(Cell { buf: b"b" })));
+    assert_eq!(csv.next_back(), None);
+    assert_csv!(csv, EOF);
+    assert_csv!(csv, position == 2);
+}
+
+#[test]
+fn into_rows_rev() {
+    let mut iter = Csv::new(b"a,b,c\n1,2,3\n4,5,6\n").into_rows();
+
+    let [a, b, c] = iter.next_back().unwrap().unwrap();
+    assert_eq_cell!(a, b"4");
+    assert_eq_cell!(b, b"5");
+    assert_eq_cell!(c, b"6");
+
+    let [a, b, c] = iter.next().unwrap().unwrap();
+    assert_eq_cell!(a, b"a");
+    assert_eq_cell!(b, b"b");
+    assert_eq_cell!(c, b"c");
+
+    let [a, b, c] = iter.next_back().unwrap().unwrap();
+    assert_eq_cell!(a, b"1");
+    assert_eq_cell!(b, b"2");
+    assert_eq_cell!(c, b"3");
+
+    assert!(iter.next_back().is_none());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn into_rows_rev_malformed() {
+    let mut iter = Csv::new(b"a,b,c\n1,2\n").into_rows::<3>();
+    assert!(iter.next_back().unwrap().is_err());
+
+    let mut iter = Csv::new(b"a,b,c,d\n1,2,3\n").into_rows::<3>();
+    assert!(iter.next_back().unwrap().is_ok());
+    assert!(iter.next_back().unwrap().is_err());
+}
+
+#[test]
+fn into_rows_with_range_rev() {
+    for input in [&b"a,b,c\n1,2,3\n4,5,6\n"[..], b"a,b,c\n1,2,3\n4,5,6"] {
+        let forward: Vec<_> = Csv::new(input)
+            .into_rows_with_range::<3>()
+            .map(Result::unwrap)
+            .collect();
+        let mut backward: Vec<_> = Csv::new(input)
+            .into_rows_with_range::<3>()
+            .rev()
+            .map(Result::unwrap)
+            .collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+}
+
+#[test]
+fn into_rows_containing() {
+    let csv = Csv::new(b"id,name\n1,\"foo\nbar\"\n2,baz\n3,\"a,\"\"bar\"\"\"\n4,qux");
+    let mut iter = csv.into_rows_containing(b"bar");
+
+    let ([id, name], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(id, b"1");
+    assert_eq_cell!(name, b"\"foo\nbar\"");
+    assert_eq!(range, 8..20);
+
+    let ([id, name], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(id, b"3");
+    assert_eq_cell!(name, b"\"a,\"\"bar\"\"\"");
+    assert_eq!(range, 26..40);
+
+    assert!(iter.next().is_none());
+
+    // Matches on the last row without a trailing newline, and rows with wrong column counts.
+    let mut iter = Csv::new(b"a,b\nqux\n1,qux").into_rows_containing::<2>(b"qux");
+    assert!(iter.next().unwrap().is_err());
+    let ([a, b], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(a, b"1");
+    assert_eq_cell!(b, b"qux");
+    assert_eq!(range, 8..13);
+    assert!(iter.next().is_none());
+
+    // Matches inside a quoted cell spanning lines, with escaped quotes before and after them.
+    let mut iter = Csv::new(b"x,\"a\n\"\"q\"\"\nb\"\r\ny,q\n").into_rows_containing::<2>(b"q");
+    let ([x, a], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(x, b"x");
+    assert_eq_cell!(a, b"\"a\n\"\"q\"\"\nb\"");
+    assert_eq!(range, 0..15);
+    let ([y, q], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(y, b"y");
+    assert_eq_cell!(q, b"q");
+    assert_eq!(range, 15..19);
+    assert!(iter.next().is_none());
+
+    // An empty needle matches every row, and stops after a trailing separator.
+    let mut iter = Csv::new(b"a,").into_rows_containing::<2>(b"");
+    let ([a, empty], range) = iter.next().unwrap().unwrap();
+    assert_eq_cell!(a, b"a");
+    assert_eq_cell!(empty, b"");
+    assert_eq!(range, 0..2);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    assert_eq!(
+        Csv::new(
+            b"1,2
+3,4
+"
+        )
+        .into_rows_containing::<2>(b"")
+        .count(),
+        2
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn write_json() {
+    use lazycsv::{
+        LineEnding,
+        json::{JsonError, JsonFormat, JsonOptions, write_json},
+    };
+
+    let data = b"name,count,ok,code\r\n\"a\tb\\\"\"c\"\"\",1.5e3,TRUE,007\r\nd,,false,-0\r\n";
+    let mut out = Vec::new();
+    assert_eq!(
+        write_json(Csv::new(data), &mut out, JsonOptions::default()).unwrap(),
+        2
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            r#"{"name":"a\tb\\\"c\"","count":"1.5e3","ok":"TRUE","code":"007"}"#,
+            "\n",
+            r#"{"name":"d","count":"","ok":"false","code":"-0"}"#,
+            "\n",
+        ),
+    );
+
+    let mut out = Vec::new();
+    let options = JsonOptions {
+        format: JsonFormat::Array,
+        infer_types: true,
+        ..Default::default()
+    };
+    write_json(Csv::new(data), &mut out, options).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "[\n",
+            r#"{"name":"a\tb\\\"c\"","count":1.5e3,"ok":true,"code":"007"},"#,
+            "\n",
+            r#"{"name":"d","count":null,"ok":false,"code":"-0"}"#,
+            "\n]\n",
+        ),
+    );
+
+    let mut out = Vec::new();
+    write_json(Csv::new(b"a,b\n"), &mut out, options).unwrap();
+    assert_eq!(out, b"[]\n");
+
+    let mut out = Vec::new();
+    let options = JsonOptions {
+        line_ending: LineEnding::Crlf,
+        ..options
+    };
+    write_json(Csv::new(b"a\n1\n2\n"), &mut out, options).unwrap();
+    assert_eq!(out, b"[\r\n{\"a\":1},\r\n{\"a\":2}\r\n]\r\n");
+
+    let err = write_json(Csv::new(b"a,b\n1,2\n3\n"), Vec::new(), options).unwrap_err();
+    assert!(matches!(err, JsonError::Row { position: 8, .. }));
+    let err = write_json(Csv::new(b"a\n\xff\n"), Vec::new(), options).unwrap_err();
+    assert!(matches!(err, JsonError::InvalidUtf8 { position: 2 }));
+    let err = write_json(Csv::new(b"a,b\n1,\xff\n"), Vec::new(), options).unwrap_err();
+    assert!(matches!(err, JsonError::InvalidUtf8 { position: 6 }));
+    let err = write_json(Csv::new(b"a,b,\"a\"\n1,2,3\n"), Vec::new(), options).unwrap_err();
+    assert!(matches!(err, JsonError::DuplicateHeader { position: 4 }));
+
+    // Stray quotes in a quoted cell are kept rather than swallowing the next byte.
+    let mut out = Vec::new();
+    let data = "h\n\"a\"b\"\"\n\"a\"é\"\"\n";
+    write_json(Csv::new(data.as_bytes()), &mut out, JsonOptions::default()).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(r#"{"h":"a\"b\""}"#, "\n", r#"{"h":"a\"é\""}"#, "\n"),
+    );
+}
+
+#[test]
+fn line_ending_stats() {
+    use lazycsv::{LineEnding, LineEndingStats};
+
+    let data = b"a,\"b\r\n\n\"\r\n1,2\n3,4\r\n5,6";
+    let stats = Csv::new(data).line_ending_stats();
+    assert_eq!(stats, LineEndingStats { lf: 1, crlf: 2 });
+    assert_eq!(stats.dominant(), Some(LineEnding::Crlf));
+    assert!(stats.is_mixed());
+
+    // Only the remaining rows are counted, including a pending line break.
+    let mut csv = Csv::new(data);
+    csv.next();
+    csv.next();
+    assert_eq!(csv.line_ending_stats(), stats);
+    csv.by_ref()
+        .filter(|item| *item == CsvIterItem::LineEnd)
+        .nth(1);
+    let stats = csv.line_ending_stats();
+    assert_eq!(stats, LineEndingStats { lf: 0, crlf: 1 });
+    assert!(!stats.is_mixed());
+
+    let stats = Csv::new(b"a\nb\r\n").line_ending_stats();
+    assert_eq!(stats.dominant(), Some(LineEnding::Lf));
+    assert_eq!(Csv::new(b"a,b").line_ending_stats().dominant(), None);
+}
+
+#[test]
+fn count_records() {
+    let inputs: [&[u8]; 6] = [
+        b"a,b\n\"1\n,2\",3\r\n4,5",
+        b"a,b\n1,2\n",
+        b"a\n\nb;c\n",
+        b"\"\"\"\",\"\n\"\n",
+        b"\n",
+        b"",
+    ];
+    for data in inputs {
+        let items = || Csv::new(data);
+        let records = items().filter(|item| *item == CsvIterItem::LineEnd).count()
+            + !(data.is_empty() || data.ends_with(b"\n")) as usize;
+        let cells = items()
+            .filter(|item| matches!(item, CsvIterItem::Cell(_)))
+            .count();
+        assert_eq!(Csv::new(data).count_records(), records, "{data:?}");
+        assert_eq!(Csv::new(data).count_cells(), cells, "{data:?}");
+    }
+
+    let data = b"a,b\n1,\"2\n\"\r\n3,4";
+    let csv = Csv::with_separator(data, b';');
+    assert_eq!((csv.count_records(), csv.count_cells()), (3, 3));
+
+    // Only the remaining records and cells are counted, including the current record.
+    let mut csv = Csv::new(data);
+    csv.next();
+    assert_eq!((csv.count_records(), csv.count_cells()), (3, 5));
+    csv.next();
+    assert_eq!((csv.count_records(), csv.count_cells()), (3, 4));
+    csv.next();
+    assert_eq!((csv.count_records(), csv.count_cells()), (2, 4));
+    csv.by_ref().for_each(drop);
+    assert_eq!((csv.count_records(), csv.count_cells()), (0, 0));
+
+    // The rest of the input after an unterminated quote is a single cell.
+    let csv = Csv::new(b"a,\"b\nc,d\n");
+    assert_eq!((csv.count_records(), csv.count_cells()), (1, 2));
+}
+
+#[test]
+fn column() {
+    use lazycsv::RowIterError;
+
+    let data = b"isrc,\"ti\"\"tle\",units\r\nUSRC17607839,\"A\nB\",\"3\"\r\nGBAYE0601498,b\r\n\r\nx,\"y,z\"";
+    let cells: Vec<_> = Csv::new(data).column(1).collect();
+    assert_eq!(
+        cells,
+        [
+            Ok(Cell {
+                buf: b"\"ti\"\"tle\""
+            }),
+            Ok(Cell { buf: b"\"A\nB\"" }),
+            Ok(Cell { buf: b"b" }),
+            Err(RowIterError::ColumnCountSmallerThanExpected {
+                expected: 2,
+                actual: 1,
+            }),
+            Ok(Cell { buf: b"\"y,z\"" }),
+        ]
+    );
+
+    let cells: Vec<_> = Csv::new(data).column(2).skip(1).collect();
+    assert_eq!(
+        cells,
+        [
+            Ok(Cell { buf: b"\"3\"" }),
+            Err(RowIterError::ColumnCountSmallerThanExpected {
+                expected: 3,
+                actual: 2,
+            }),
+            Err(RowIterError::ColumnCountSmallerThanExpected {
+                expected: 3,
+                actual: 1,
+            }),
+            Err(RowIterError::ColumnCountSmallerThanExpected {
+                expected: 3,
+                actual: 2,
+            }),
+        ]
+    );
+
+    let mut titles = Csv::new(data).column_by_name(b"ti\"tle").unwrap();
+    assert_eq!(titles.next(), Some(Ok(Cell { buf: b"\"A\nB\"" })));
+    assert!(Csv::new(data).column_by_name(b"ti\"\"tle").is_none());
+    assert!(Csv::new(data).column_by_name(b"title").is_none());
+    assert!(Csv::new(b"").column_by_name(b"").is_none());
+
+    #[cfg(feature = "alloc")]
+    {
+        let cells = Csv::new(b"a,1\nb,\"2\"\n").collect_column(1);
+        assert_eq!(cells, Ok(vec![Cell { buf: b"1" }, Cell { buf: b"\"2\"" }]));
+        assert!(Csv::new(data).collect_column(1).is_err());
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn owned_csv() {
+    use std::sync::Arc;
+
+    use lazycsv::{
+        RowIterError,
+        owned::{OwnedCell, OwnedCsv, OwnedCsvIterItem},
+    };
+
+    fn is_static<T: Send + Sync + 'static>(_: &T) {}
+
+    let buf: Arc<[u8]> = Arc::from(&b"a;\"b;c\"\r\n1;2\n3\n"[..]);
+    let mut csv = OwnedCsv::with_separator(buf.clone(), b';');
+    let Some(OwnedCsvIterItem::Cell(cell)) = csv.next() else {
+        panic!("Expected a cell");
+    };
+    is_static(&cell);
+    assert_eq!(cell.as_bytes(), b"a");
+    assert_eq!(cell.range(), 0..1);
+    assert!(Arc::ptr_eq(cell.buffer(), &buf));
+
+    let cell = match csv.next() {
+        Some(OwnedCsvIterItem::Cell(cell)) => cell,
+        other => panic!("Expected a cell, got {other:?}"),
+    };
+    drop(csv);
+    assert_eq!(cell.as_cell().try_as_str().unwrap(), "b;c");
+    assert_eq!(cell.range(), 2..7);
+
+    let mut rows = OwnedCsv::with_separator(buf.clone(), b';')
+        .skip_rows(1)
+        .into_rows::<2>();
+    let [one, two] = rows.next().unwrap().unwrap();
+    assert_eq!((one.as_bytes(), two.range()), (&b"1"[..], 11..12));
+    assert_eq!(
+        rows.next(),
+        Some(Err(RowIterError::ColumnCountSmallerThanExpected {
+            expected: 2,
+            actual: 1,
+        }))
+    );
+    assert_eq!(rows.next(), None);
+
+    // Cells compare by their contents, regardless of the buffer.
+    let other: OwnedCell = OwnedCsv::new(Arc::from(&b"x,1"[..]))
+        .into_rows::<2>()
+        .next()
+        .unwrap()
+        .unwrap()[1]
+        .clone();
+    assert_eq!(one, other);
+
+    // Borrowing iterators continue from the current position.
+    let mut csv = OwnedCsv::with_separator(buf, b';');
+    csv.by_ref().take(3).for_each(drop);
+    assert_eq!(csv.position(), 9);
+    assert_eq!(csv.as_csv().count_records(), 2);
+    assert_eq!(csv.next(), Some(OwnedCsvIterItem::Cell(one)));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn csv_records() {
+    use lazycsv::csv::{to_byte_record, to_string_record};
+
+    let data =
+        b"isrc,title\nUSRC17607839,\"Hi,\r\n\"\"Quote\"\"\"\n\"\",\"\"\"\"\nGBAYE0601498,\xff,x";
+    let expected: Vec<_> = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(&data[..])
+        .into_byte_records()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let records: Vec<_> = Csv::new(data).into_byte_records().collect();
+    assert_eq!(records, expected);
+    for (record, expected) in records.iter().zip(&expected) {
+        assert_eq!(record.position(), expected.position());
+    }
+
+    let records: Vec<_> = Csv::new(data).into_string_records().collect();
+    assert_eq!(
+        records[1].as_ref().unwrap(),
+        vec!["USRC17607839", "Hi,\r\n\"Quote\""]
+    );
+    assert_eq!(records[2].as_ref().unwrap(), vec!["", "\""]);
+    assert!(records[3].is_err());
+
+    let [a, b] = Csv::new(b"\"a\"\"\",b")
+        .into_rows()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(to_byte_record(&[a, b]), vec![&b"a\""[..], b"b"]);
+    assert_eq!(to_string_record(&[b, a]).unwrap(), vec!["b", "a\""]);
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn encoding() {
+    use std::borrow::Cow;
+
+    use lazycsv::encoding::{DecodeError, EUC_KR, UTF_8, UTF_16BE, WINDOWS_1252, to_utf8};
+
+    // "Café","""Ñ""",값 in Windows-1252, except for the last cell in CP949
+    let data = b"\"Caf\xe9\",\"\"\"\xd1\"\"\",\xb0\xaa\n";
+    let [cafe, quoted, value] = Csv::new(data).into_rows().next().unwrap().unwrap();
+    assert_eq!(cafe.decode(WINDOWS_1252), Ok(Cow::Owned("Café".into())));
+    assert_eq!(quoted.decode(WINDOWS_1252).unwrap(), "\"Ñ\"");
+    assert_eq!(value.decode(EUC_KR).unwrap(), "값");
+    assert_eq!(value.decode(UTF_8), Err(DecodeError { encoding: "UTF-8" }));
+    assert!(matches!(
+        Cell { buf: b"\"a\"" }.decode(UTF_8),
+        Ok(Cow::Borrowed("a"))
+    ));
+
+    assert!(matches!(to_utf8(b"a,b", UTF_8), Ok(Cow::Borrowed("a,b"))));
+    assert_eq!(to_utf8(b"\xef\xbb\xbfa,b", WINDOWS_1252).unwrap(), "a,b");
+    assert_eq!(to_utf8(b"\x00a\x00,\x00b", UTF_16BE).unwrap(), "a,b");
+    assert_eq!(
+        to_utf8(b"\xff\xfea\x00\xb0\x00", UTF_16BE).unwrap(),
+        "a\u{b0}"
+    );
+    assert_eq!(
+        to_utf8(b"\x00a\x00", UTF_16BE),
+        Err(DecodeError {
+            encoding: "UTF-16BE"
+        })
+    );
+}
+
+#[test]
+fn unquoted_comparison() {
+    use std::hash::{DefaultHasher, Hasher};
+
+    let hash = |buf: &[u8]| {
+        let mut hasher = DefaultHasher::new();
+        Cell { buf }.hash_unquoted(&mut hasher);
+        hasher.finish()
+    };
+
+    let cell = Cell {
+        buf: b"\"\"\"Hi\"\", said \"\"Bob\"\"\"",
+    };
+    let chunks: Vec<_> = cell.unquoted_chunks().collect();
+    assert_eq!(chunks, [&b"\""[..], b"Hi\"", b", said \"", b"Bob\""]);
+    assert!(cell.eq_unquoted(b"\"Hi\", said \"Bob\""));
+    assert!(!cell.eq_unquoted(b"\"Hi\", said \"Bob"));
+    assert!(!cell.eq_unquoted(b"\"Hi\", said \"Bob\"\""));
+    assert!(cell.eq_unquoted_ignore_ascii_case(b"\"hi\", SAID \"bob\""));
+    assert!(cell.starts_with_unquoted(b""));
+    assert!(cell.starts_with_unquoted(b"\"Hi\", s"));
+    assert!(cell.starts_with_unquoted(b"\"Hi\", said \"Bob\""));
+    assert!(!cell.starts_with_unquoted(b"\"Hi\"\""));
+    assert!(!cell.starts_with_unquoted(b"\"Hi\", said \"Bob\"!"));
+    assert_eq!(hash(b"\"Say \"\"Hi\"\"\""), hash(b"Say \"Hi\""));
+
+    let empty = Cell { buf: b"\"\"" };
+    assert_eq!(empty.unquoted_chunks().next(), None);
+    assert!(empty.eq_unquoted(b""));
+    assert_eq!(hash(empty.buf), hash(b""));
+    assert_ne!(hash(b"a"), hash(b"\"a\"\"\""));
+
+    // Lone quotes are kept, as in `Cell::try_as_str()`.
+    let malformed = Cell {
+        buf: "\"a\"é\"\"\"\"".as_bytes(),
+    };
+    assert_eq!(
+        malformed.unquoted_chunks().collect::<Vec<_>>(),
+        [&b"a\""[..], "é\"".as_bytes(), b"\""]
+    );
+    assert!(malformed.eq_unquoted("a\"é\"\"".as_bytes()));
+
+    // Consecutive cells can't shift their boundary, even if they contain arbitrary bytes.
+    let hash_cells = |cells: [&[u8]; 2]| {
+        let mut hasher = DefaultHasher::new();
+        for buf in cells {
+            Cell { buf }.hash_unquoted(&mut hasher);
+        }
+        hasher.finish()
+    };
+    assert_ne!(hash_cells([b"a\xff", b"b"]), hash_cells([b"a", b"\xffb"]));
+    assert_ne!(hash_cells([b"\xff", b""]), hash_cells([b"", b"\xff"]));
+    assert_eq!(
+        hash_cells([b"a\xff", b"b"]),
+        hash_cells([b"\"a\xff\"", b"b"])
+    );
+
+    // Hashing doesn't depend on where escaped quotes split the content.
+    let unquoted: Vec<u8> = (0..200).map(|i| [b'x', b'"'][i % 7 / 6]).collect();
+    let mut quoted = vec![b'"'];
+    for &byte in &unquoted {
+        quoted.extend_from_slice(if byte == b'"' { b"\"\"" } else { b"x" });
+    }
+    quoted.push(b'"');
+    assert!(Cell { buf: &quoted }.eq_unquoted(&unquoted));
+    assert_eq!(hash(&quoted), hash(&unquoted));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn unquoted_chunks_match_try_as_str() {
+    let cells: [&[u8]; 8] = [
+        b"",
+        b"\"",
+        b"\"\"",
+        b"\"ab",
+        b"ab\"",
+        b"a\"\"b",
+        b"\"a\"b\"",
+        b"\"a\"\"\"\"b\"",
+    ];
+    for buf in cells {
+        let cell = Cell { buf };
+        let chunks: Vec<u8> = cell.unquoted_chunks().flatten().copied().collect();
+        assert_eq!(chunks, cell.try_as_str().unwrap().as_bytes(), "{cell:?}");
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn dequote_buf() {
+    use lazycsv::dequote::DequoteBuf;
+
+    let data = b"\"Hi, \"\"Quote\"\"\",,\"\"\na,\"\"\"\"\"\",\"\xff\"\n";
+    let mut rows = Csv::new(data).into_rows::<3>();
+    let mut buf = DequoteBuf::new();
+
+    let row = rows.next().unwrap().unwrap();
+    let mut cells = buf.dequote(&row).unwrap();
+    assert_eq!(cells.len(), 3);
+    assert_eq!(cells.get(2), Some(""));
+    assert_eq!(cells.next(), Some("Hi, \"Quote\""));
+    assert_eq!(cells.get(0), Some(""));
+    assert_eq!(cells.get(2), None);
+    assert_eq!(cells.collect::<Vec<_>>(), ["", ""]);
+    assert_eq!(buf.dequote_row(&row), Ok(["Hi, \"Quote\"", "", ""]));
+
+    let row = rows.next().unwrap().unwrap();
+    assert_eq!(
+        buf.dequote(&row).err(),
+        row[2].try_as_str().err(),
+        "invalid UTF-8 is reported like `Cell::try_as_str()`"
+    );
+    assert_eq!(buf.dequote_row(&[row[1], row[0]]), Ok(["\"\"", "a"]));
+    assert_eq!(buf.dequote(&[]).unwrap().next(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn dedup() {
+    use lazycsv::{RowIterError, dedup::Dedup};
+
+    let data = b"abc,1\n\"abc\",\"1\"\nabc\n\"ab\",c1\nabc,1\r\nabc\n\"a\"\"\",\n\"a\"\"\",\"\"\n";
+    let mut rows = Dedup::new(Csv::new(data).into_rows::<2>());
+    let error = RowIterError::ColumnCountSmallerThanExpected {
+        expected: 2,
+        actual: 1,
+    };
+    assert_eq!(
+        rows.by_ref().collect::<Vec<_>>(),
+        [
+            Ok([Cell { buf: b"abc" }, Cell { buf: b"1" }]),
+            Err(error.clone()),
+            Ok([Cell { buf: b"\"ab\"" }, Cell { buf: b"c1" }]),
+            Err(error),
+            Ok([Cell { buf: b"\"a\"\"\"" }, Cell { buf: b"" }]),
+        ]
+    );
+    assert_eq!((rows.distinct(), rows.duplicates()), (3, 3));
+
+    // Cells with bytes other than UTF-8, e.g. `ÿ` in Windows-1252, don't shift between columns.
+    let rows = Dedup::new(Csv::new(b"a\xff,b\na,\xffb\n").into_rows::<2>());
+    assert_eq!(rows.count(), 2);
+
+    // Rows with a range are deduplicated by their cells only.
+    let rows: Vec<_> = Dedup::new(Csv::new(b"a\n\"a\"\nb\n").into_rows_with_range::<1>())
+        .map(|row| row.unwrap().1)
+        .collect();
+    assert_eq!(rows, [0..2, 6..8]);
+
+    // Records streamed from a reader are deduplicated the same way as rows.
+    let records: Vec<_> = Dedup::new(lazycsv::io::CsvReader::new(&data[..]))
+        .map(|record| record.unwrap().position())
+        .collect();
+    assert_eq!(records, [0, 16, 20, 39]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn normalize_line_endings() {
+    use lazycsv::{LineEnding, io::normalize_line_endings};
+
+    let data = b"a,\"b\r\n\n\"\r\n1,2\n\n3,4\r\n";
+    let mut out = Vec::new();
+    normalize_line_endings(Csv::new(data), &mut out, LineEnding::Lf).unwrap();
+    assert_eq!(out, b"a,\"b\r\n\n\"\n1,2\n\n3,4\n");
+
+    let mut out = Vec::new();
+    let mut csv = Csv::new(data);
+    csv.by_ref().find(|item| *item == CsvIterItem::LineEnd);
+    normalize_line_endings(csv, &mut out, LineEnding::Crlf).unwrap();
+    assert_eq!(out, b"1,2\r\n\r\n3,4\r\n");
+}
+
+#[test]
+fn into_items_with_range() {
+    let data = b"a,\"b\nc\"\r\n,d,";
+    let items: Vec<_> = Csv::new(data).into_items_with_range().collect();
+    assert_eq!(
+        items,
+        [
+            (CsvIterItem::Cell(Cell { buf: b"a" }), 0..1),
+            (CsvIterItem::Cell(Cell { buf: b"\"b\nc\"" }), 2..7),
+            (CsvIterItem::LineEnd, 7..9),
+            (CsvIterItem::Cell(Cell { buf: b"" }), 9..9),
+            (CsvIterItem::Cell(Cell { buf: b"d" }), 10..11),
+            (CsvIterItem::Cell(Cell { buf: b"" }), 12..12),
+        ]
+    );
+    for (item, range) in items {
+        if let CsvIterItem::Cell(cell) = item {
+            assert_eq!(&data[range], cell.buf);
+        }
+    }
+}
+
+#[test]
+fn into_rows_with_cell_range() {
+    let mut iter = Csv::new(b"a,b\r\n1,\"2\"\n3").into_rows_with_cell_range();
+
+    let ([a, b], range) = iter.next().unwrap().unwrap();
+    assert_eq!((a.cell.buf, a.range), (&b"a"[..], 0..1));
+    assert_eq!((b.cell.buf, b.range), (&b"b"[..], 2..3));
+    assert_eq!(range, 0..5);
+
+    let ([one, two], range) = iter.next().unwrap().unwrap();
+    assert_eq!((one.cell.buf, one.range), (&b"1"[..], 5..6));
+    assert_eq!((two.cell.buf, two.range), (&b"\"2\""[..], 7..10));
+    assert_eq!(range, 5..11);
+
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn into_rows_lenient() {
+    use lazycsv::{
+        QuoteError, RowIterError,
+        lenient::{ErrorBudgetExceeded, SkipReason},
+    };
+
+    // A stray quote on the second line would otherwise swallow the lines up to the next quote.
+    let data = b"1,\"a\"\r\n2,b\"c\n3,d\n4,\"e\n5\n6,f,g\n7,\"h\"\"\"\n8,\"i";
+    let mut iter = Csv::new(data).into_rows_lenient(usize::MAX);
+    let rows: Vec<_> = iter
+        .by_ref()
+        .map(|row| {
+            let ([a, b], range) = row.unwrap();
+            assert_eq!(&data[range.clone()][..a.buf.len()], a.buf);
+            (b.buf, range)
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (&b"\"a\""[..], 0..7),
+            (b"d", 13..17),
+            (b"\"h\"\"\"", 30..38),
+        ]
+    );
+
+    let skipped = iter.into_skipped();
+    let skipped: Vec<_> = skipped
+        .iter()
+        .map(|s| (s.range.clone(), &s.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            (
+                7..13,
+                &SkipReason::Quoting {
+                    column: 1,
+                    source: QuoteError::QuoteInUnquotedCell,
+                },
+            ),
+            (
+                17..22,
+                &SkipReason::Quoting {
+                    column: 1,
+                    source: QuoteError::DataAfterClosingQuote,
+                },
+            ),
+            (
+                22..24,
+                &SkipReason::ColumnCount(RowIterError::ColumnCountSmallerThanExpected {
+                    expected: 2,
+                    actual: 1,
+                }),
+            ),
+            (
+                24..30,
+                &SkipReason::ColumnCount(RowIterError::ColumnCountLargerThanExpected {
+                    expected: 2,
+                }),
+            ),
+            (38..42, &SkipReason::UnterminatedQuote),
+        ]
+    );
+
+    // Iteration stops once the error budget is exceeded.
+    let mut iter = Csv::new(data).into_rows_lenient::<2>(1);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    assert_eq!(
+        iter.next(),
+        Some(Err(ErrorBudgetExceeded { max_errors: 1 }))
+    );
+    assert!(iter.next().is_none());
+    assert_eq!(iter.skipped().len(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn editor() {
+    use lazycsv::edit::{EditError, Editor};
+
+    let data = b"a;b\n\"x\"\"\";1\r\n2;\"y\nz\"\n3;4";
+    let mut editor = Editor::with_separator(data, b';');
+    assert_eq!(editor.to_vec(), data);
+
+    let rows: Vec<_> = Csv::with_separator(data, b';')
+        .into_rows_with_cell_range::<2>()
+        .map(Result::unwrap)
+        .collect();
+    editor.replace(rows[1].0[1].range.clone(), b"1;5").unwrap();
+    editor.replace(rows[2].0[0].range.clone(), b"q\"").unwrap();
+    // The last row has no line break, so one is inserted before the appended rows.
+    editor.delete(rows[3].0[1].range.clone()).unwrap();
+    editor.append_row([&b""[..], b"n"]);
+    editor.append_row([&b"\r"[..]]);
+    assert_eq!(
+        editor.to_vec(),
+        b"a;b\n\"x\"\"\";\"1;5\"\r\n\"q\"\"\";\"y\nz\"\n3;\n;n\n\"\r\"\n",
+    );
+
+    assert_eq!(
+        editor.delete(rows[2].1.clone()),
+        Err(EditError::Overlap {
+            range: 13..21,
+            existing: 13..14,
+        })
+    );
+    assert_eq!(
+        editor.delete(20..30),
+        Err(EditError::OutOfBounds {
+            range: 20..30,
+            len: 24,
+        })
+    );
+
+    // Deleting the last row entirely keeps the previous line break.
+    let mut editor = Editor::new(b"a\r\nb");
+    editor.delete(3..4).unwrap();
+    editor.append_row([&b"c"[..]]);
+    let mut out = Vec::new();
+    #[cfg(feature = "std")]
+    editor.write_to(&mut out).unwrap();
+    #[cfg(not(feature = "std"))]
+    out.extend(editor.to_vec());
+    assert_eq!(out, b"a\r\nc\r\n");
+}
+
+#[test]
+fn into_limited() {
+    use lazycsv::limit::{LimitError, Limits};
+
+    fn first_error(data: &[u8], limits: Limits) -> Option<LimitError> {
+        Csv::new(data).into_limited(limits).find_map(Result::err)
+    }
+
+    let data = b"a,\"b\r\nc\",dd\r\n\"eee\",f\n,";
+    let items: Vec<_> = Csv::new(data)
+        .into_limited(Limits::default())
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(items, Csv::new(data).collect::<Vec<_>>());
+
+    // Each limit is inclusive.
+    let limits = Limits {
+        max_cell_bytes: 6,
+        max_record_bytes: 11,
+        max_columns: 3,
+        max_records: 3,
+    };
+    assert_eq!(first_error(data, limits), None);
+
+    let limits = Limits {
+        max_cell_bytes: 5,
+        ..Default::default()
+    };
+    assert_eq!(
+        first_error(data, limits),
+        Some(LimitError::CellTooLong {
+            position: 2,
+            limit: 5,
+        })
+    );
+    let limits = Limits {
+        max_record_bytes: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        first_error(data, limits),
+        Some(LimitError::RecordTooLong {
+            position: 0,
+            limit: 10,
+        })
+    );
+    let limits = Limits {
+        max_columns: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        first_error(data, limits),
+        Some(LimitError::TooManyColumns {
+            position: 0,
+            limit: 2,
+        })
+    );
+    let limits = Limits {
+        max_records: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        first_error(data, limits),
+        Some(LimitError::TooManyRecords {
+            position: 21,
+            limit: 2,
+        })
+    );
+
+    // An unterminated quote is only scanned up to the limit.
+    let mut data = b"a,\"b".to_vec();
+    data.resize(1 << 20, b'x');
+    let limits = Limits {
+        max_cell_bytes: 16,
+        ..Default::default()
+    };
+    let mut iter = Csv::new(&data).into_limited(limits);
+    assert!(iter.next().unwrap().is_ok());
+    assert_eq!(
+        iter.next(),
+        Some(Err(LimitError::CellTooLong {
+            position: 2,
+            limit: 16,
+        }))
+    );
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn into_typed_rows() {
+    use lazycsv::{
+        RowIterError,
+        typed::{FromCellError, TypedRowError},
+    };
+
+    let data =
+        b"1,\"a,b\",-2.5,\n2,c,3,4\n3,\"d\"\"\",0,5\nx,e,1,2\n4,f,1\n5,g,1,2,3\n6,h,\"7\",\"\"";
+    let rows: Vec<_> = Csv::new(data)
+        .into_typed_rows::<(u32, &str, f64, Option<u64>)>()
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            Ok((1, "a,b", -2.5, None)),
+            Ok((2, "c", 3.0, Some(4))),
+            Err(TypedRowError::Cell {
+                column: 1,
+                position: 24,
+                source: FromCellError::EscapedQuote,
+            }),
+            Err(TypedRowError::Cell {
+                column: 0,
+                position: 34,
+                source: "x".parse::<u32>().unwrap_err().into(),
+            }),
+            Err(TypedRowError::Row(
+                RowIterError::ColumnCountSmallerThanExpected {
+                    expected: 4,
+                    actual: 3,
+                }
+            )),
+            Err(TypedRowError::Row(
+                RowIterError::ColumnCountLargerThanExpected { expected: 4 }
+            )),
+            // A quoted empty cell is not null.
+            Err(TypedRowError::Cell {
+                column: 3,
+                position: 66,
+                source: "".parse::<u64>().unwrap_err().into(),
+            }),
+        ]
+    );
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut rows = Csv::new(data)
+            .into_typed_rows::<(Cell, String, bool)>()
+            .skip(2);
+        assert!(rows.next().unwrap().is_err());
+        let mut rows = Csv::new(b"\"d\"\"\",true\n").into_typed_rows::<(String, bool)>();
+        assert_eq!(rows.next(), Some(Ok(("d\"".to_string(), true))));
+        assert_eq!(rows.next(), None);
+
+        // Tuples have no header names, so the header row is only skipped.
+        let mut rows = Csv::new(b"a,b\n1,2\n")
+            .into_typed_rows_by_header::<(u8, u8)>()
+            .unwrap();
+        assert_eq!(rows.next(), Some(Ok((1, 2))));
+        assert_eq!(rows.next(), None);
+    }
+}
+
+#[test]
+fn fixed_width() {
+    use lazycsv::fixed_width::{Column, FixedWidth, FixedWidthError, Trim};
+
+    let layout = [
+        Column {
+            start: 0,
+            width: 3,
+            trim: Trim::None,
+        },
+        Column {
+            start: 3,
+            width: 4,
+            trim: Trim::Start,
+        },
+        Column {
+            start: 7,
+            width: 5,
+            trim: Trim::Both,
+        },
+    ];
+    let data = b"id   n name\r\n001  12 foo \n002 345 bar\n003   6\n04";
+    let mut iter = FixedWidth::new(data, layout).skip_rows(1);
+    assert_eq!(iter.position(), 13);
+
+    let [id, n, name] = iter.next().unwrap().unwrap();
+    assert_eq_cell!(id, b"001");
+    assert_eq_cell!(n, b"12");
+    assert_eq_cell!(name, b"foo");
+
+    // Stripped trailing spaces are tolerated in columns trimming them.
+    let [id, n, name] = iter.next().unwrap().unwrap();
+    assert_eq_cell!(id, b"002");
+    assert_eq_cell!(n, b"345");
+    assert_eq_cell!(name, b"bar");
+    let [id, n, name] = iter.next().unwrap().unwrap();
+    assert_eq_cell!(id, b"003");
+    assert_eq_cell!(n, b"6");
+    assert_eq_cell!(name, b"");
+
+    assert_eq!(
+        iter.next(),
+        Some(Err(FixedWidthError::LineTooShort {
+            column: 0,
+            expected: 3,
+            actual: 2,
+        }))
+    );
+    assert!(iter.next().is_none());
+
+    // A huge width doesn't overflow, and takes the rest of the line if it's trimmed.
+    let layout = [
+        Column {
+            start: 1,
+            width: usize::MAX,
+            trim: Trim::End,
+        },
+        Column {
+            start: 0,
+            width: usize::MAX,
+            trim: Trim::None,
+        },
+    ];
+    let mut iter = FixedWidth::new(b"abc  \n", layout);
+    assert_eq!(
+        iter.next(),
+        Some(Err(FixedWidthError::LineTooShort {
+            column: 1,
+            expected: usize::MAX,
+            actual: 5,
+        }))
+    );
+    let mut iter = FixedWidth::new(b"abc  \n", [layout[0]]);
+    let [rest] = iter.next().unwrap().unwrap();
+    assert_eq_cell!(rest, b"bc");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn fixed_width_quotes() {
+    use lazycsv::fixed_width::{Column, FixedWidth, Trim};
+
+    // Cells with a lone or unterminated quote are kept as-is rather than dequoted.
+    let layout = [
+        Column {
+            start: 0,
+            width: 1,
+            trim: Trim::None,
+        },
+        Column {
+            start: 1,
+            width: 4,
+            trim: Trim::End,
+        },
+    ];
+    let [quote, rest] = FixedWidth::new("\"\"é \n".as_bytes(), layout)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(quote.try_as_str().unwrap(), "\"");
+    assert_eq!(rest.try_as_str().unwrap(), "\"é");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn push_parser_matches_pull_parser() {
+    use lazycsv::push::{PushError, PushParser};
+
+    #[derive(Debug, PartialEq)]
+    enum Item {
+        Cell(Vec<u8>),
+        LineEnd,
+    }
+
+    fn owned(item: CsvIterItem<'_>) -> Item {
+        match item {
+            CsvIterItem::Cell(cell) => Item::Cell(cell.buf.to_vec()),
+            CsvIterItem::LineEnd => Item::LineEnd,
+        }
+    }
+
+    let inputs: &[&[u8]] = &[
+        b"",
+        b"a,b,c\n1,2,3\n4,5,6",
+        b"aaa,bbb\r\n100,200\r\n",
+        b"aaa,\n100,",
+        b"\n\r\n,",
+        br#""Hello, world!","Hi ""Quote""","""HELLO""","""name""""#,
+        b"\"multi\r\nline\",\"\"\r\n\"\",x\n",
+    ];
+
+    for input in inputs {
+        let expected: Vec<_> = Csv::new(input).map(owned).collect();
+        for chunk_size in 1..=input.len().max(1) {
+            let mut parser = PushParser::<32>::new();
+            let mut items = Vec::new();
+            for chunk in input.chunks(chunk_size) {
+                parser.feed(chunk, |item| items.push(owned(item))).unwrap();
+            }
+            parser.finish(|item| items.push(owned(item))).unwrap();
+            assert_eq!(
+                expected,
+                items,
+                "input: {:?}, chunk size: {chunk_size}",
+                input.escape_ascii()
+            );
+        }
+    }
+
+    let mut parser = PushParser::<4>::with_separator(b'\t');
+    let mut items = Vec::new();
+    parser
+        .feed(b"ab\tcdef", |item| items.push(owned(item)))
+        .unwrap();
+    assert_eq!(
+        parser.feed(b"g\th", |item| items.push(owned(item))),
+        Err(PushError::CarryOverflow { capacity: 4 })
+    );
+    parser.feed(b"\n", |item| items.push(owned(item))).unwrap();
+    assert_eq!(
+        items,
+        [
+            Item::Cell(b"ab".to_vec()),
+            Item::Cell(b"h".to_vec()),
+            Item::LineEnd
+        ]
+    );
+
+    let mut parser = PushParser::<4>::new();
+    let mut items = Vec::new();
+    assert!(
+        parser
+            .feed(b"a,bcdef", |item| items.push(owned(item)))
+            .is_err()
+    );
+    parser
+        .feed(b"gh,i", |item| items.push(owned(item)))
+        .unwrap();
+    parser.finish(|item| items.push(owned(item))).unwrap();
+    assert_eq!(
+        items,
+        [Item::Cell(b"a".to_vec()), Item::Cell(b"i".to_vec())]
+    );
+
+    let mut parser = PushParser::<4>::new();
+    parser.feed(b"\"a", |_| ()).unwrap();
+    assert_eq!(parser.finish(|_| ()), Err(PushError::UnterminatedQuote));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_csv_reader() {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures::{StreamExt, executor::block_on};
+    use lazycsv::tokio::AsyncCsvReader;
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    /// Yields a single byte per read, returning `Pending` in between.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        pending: bool,
+    }
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.pending = !self.pending;
+            if self.pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            if let Some((&first, rest)) = self.data.split_first() {
+                buf.put_slice(&[first]);
+                self.data = rest;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    let data = b"a,\"b\r\nc\"\r\n\"\"\"\",\n,\n1,2";
+    let records: Vec<_> = block_on(
+        AsyncCsvReader::new(Trickle {
+            data,
+            pending: false,
+        })
+        .map(Result::unwrap)
+        .collect(),
+    );
+    let cells: Vec<Vec<&[u8]>> = records
+        .iter()
+        .map(|record| record.iter().map(|cell| cell.buf).collect())
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            vec![&b"a"[..], b"\"b\r\nc\""],
+            vec![b"\"\"\"\"", b""],
+            vec![b"", b""],
+            vec![b"1", b"2"],
+        ]
+    );
+    assert_eq!(records[1].as_bytes(), b"\"\"\"\",\n");
+    assert_eq!(
+        records
+            .iter()
+            .map(|record| record.position())
+            .collect::<Vec<_>>(),
+        [0, 10, 16, 18]
+    );
+    assert!(records[3].to_array::<2>().is_ok());
+    assert!(records[3].to_array::<3>().is_err());
+
+    // Records larger than the internal buffer
+    let mut data = vec![b'x'; 20_000];
+    data.extend_from_slice(b",y\nz\n");
+    let records: Vec<_> = block_on(AsyncCsvReader::new(&data[..]).map(Result::unwrap).collect());
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].get(0).unwrap().buf.len(), 20_000);
+    assert_eq!(records[1].position(), 20_003);
+
+    let mut records = AsyncCsvReader::new(&b"a\n\"b\n"[..]);
+    assert!(block_on(records.next()).unwrap().is_ok());
+    assert!(block_on(records.next()).unwrap().is_err());
+    assert!(block_on(records.next()).is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn csv_reader() {
+    use std::io::Read;
+
+    use lazycsv::io::CsvReader;
+
+    /// Yields at most 3 bytes per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(3).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    let data = b"a,\"b\r\nc\"\r\n\"\"\"\",\n,\n1,2";
+    let records: Vec<_> = CsvReader::new(Trickle(data)).map(Result::unwrap).collect();
+    let cells: Vec<Vec<&[u8]>> = records
+        .iter()
+        .map(|record| record.iter().map(|cell| cell.buf).collect())
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            vec![&b"a"[..], b"\"b\r\nc\""],
+            vec![b"\"\"\"\"", b""],
+            vec![b"", b""],
+            vec![b"1", b"2"],
+        ]
+    );
+    assert_eq!(
+        records
+            .iter()
+            .map(|record| record.position())
+            .collect::<Vec<_>>(),
+        [0, 10, 16, 18]
+    );
+
+    // A long quoted cell arriving in small reads, while the buffer is compacted and grown.
+    let cell = [&b"\""[..], &b"a\n\"\"".repeat(5000), b"\""].concat();
+    let data = [&b"x\n"[..], &cell, b",y\nz"].concat();
+    let records: Vec<_> = CsvReader::new(Trickle(&data)).map(Result::unwrap).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1].get(0).unwrap().buf, cell);
+    assert_eq!(records[2].position(), cell.len() + 5);
+
+    let mut records = CsvReader::with_separator(&b"a\tb\n\"c\n"[..], b'\t');
+    assert_eq!(records.next().unwrap().unwrap().len(), 2);
+    assert!(records.next().unwrap().is_err());
+    assert!(records.next().is_none());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn csv_follower() {
+    use std::{fs::OpenOptions, io::Write, time::Duration};
+
+    use lazycsv::io::CsvFollower;
+
+    let path = std::env::temp_dir().join(format!("lazycsv-follow-{}.csv", std::process::id()));
+    std::fs::write(&path, b"a,1\nb,\"2").unwrap();
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    let mut follower = CsvFollower::open(&path).unwrap();
+    let cells = |record: std::io::Result<lazycsv::io::Record>| {
+        let record = record.unwrap();
+        let cells: Vec<_> = record.iter().map(|cell| cell.buf.to_vec()).collect();
+        (cells, record.position())
+    };
+
+    assert_eq!(
+        cells(follower.try_next().unwrap()),
+        (vec![b"a".to_vec(), b"1".to_vec()], 0)
+    );
+    // The partial record is held back, even once its quote is closed.
+    assert!(follower.try_next().is_none());
+    file.write_all(b"\n3\"").unwrap();
+    assert!(follower.try_next().is_none());
+    assert_eq!(follower.position(), 4);
+
+    file.write_all(b"\r\nc,4\n").unwrap();
+    assert_eq!(
+        cells(follower.try_next().unwrap()),
+        (vec![b"b".to_vec(), b"\"2\n3\"".to_vec()], 4)
+    );
+    assert_eq!(follower.position(), 13);
+
+    // Resuming from the stored position yields the remaining records.
+    let mut resumed = CsvFollower::open_at(&path, follower.position())
+        .unwrap()
+        .with_interval(Duration::from_millis(1));
+    assert_eq!(
+        cells(follower.next().unwrap()),
+        (vec![b"c".to_vec(), b"4".to_vec()], 13)
+    );
+    assert_eq!(
+        cells(resumed.next().unwrap()),
+        (vec![b"c".to_vec(), b"4".to_vec()], 13)
+    );
+    assert!(resumed.try_next().is_none());
+    assert_eq!(resumed.position(), 17);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn auto_decoder() {
+    use lazycsv::io::{AutoDecoder, Compression, CsvReader};
+
+    let data = b"isrc,units\nUSRC17607839,3\nGBAYE0601498,5\n";
+
+    let decoder = AutoDecoder::new(&data[..]).unwrap();
+    assert_eq!(decoder.compression(), Compression::None);
+    assert_eq!(CsvReader::new(decoder).count(), 3);
+
+    match AutoDecoder::new(&b"\x1f\x8b"[..]) {
+        Ok(decoder) => {
+            assert!(cfg!(feature = "gzip") && decoder.compression() == Compression::Gzip)
+        }
+        Err(err) => {
+            assert!(!cfg!(feature = "gzip") && err.kind() == std::io::ErrorKind::Unsupported)
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::{Read, Write};
+
+        // Concatenated gzip members are decompressed as a whole.
+        let mut compressed = Vec::new();
+        for part in [&data[..20], &data[20..]] {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(part).unwrap();
+            compressed.extend(encoder.finish().unwrap());
+        }
+        let mut decoder = AutoDecoder::new(&compressed[..]).unwrap();
+        assert_eq!(decoder.compression(), Compression::Gzip);
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[cfg(feature = "zstd")]
+    {
+        let compressed = zstd::encode_all(&data[..], 0).unwrap();
+        let decoder = AutoDecoder::new(&compressed[..]).unwrap();
+        assert_eq!(decoder.compression(), Compression::Zstd);
+        let records: Vec<_> = CsvReader::new(decoder).map(Result::unwrap).collect();
+        assert_eq!(records[2].as_bytes(), b"GBAYE0601498,5\n");
+    }
+}